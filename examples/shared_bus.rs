@@ -0,0 +1,56 @@
+// Two DRV8830s sharing one bus through `embedded-hal-bus`, run against the simulator.
+// On hardware, wrap the HAL's I2C peripheral instead of `Bus`. Use `CriticalSectionDevice`
+// when the bus is also used from interrupts, or `AtomicDevice` to get `AtomicError::Busy`
+// instead of blocking on contention.
+use core::cell::RefCell;
+
+use drv8830::sim::SimulatedDrv8830;
+use drv8830::{Address, AddressPin, Drv8830, Rounding, VSet};
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+use embedded_hal_bus::i2c::RefCellDevice;
+
+const LEFT: Address = Address::new(AddressPin::Low, AddressPin::Low);
+const RIGHT: Address = Address::new(AddressPin::Low, AddressPin::High);
+
+// Routes each transaction to the chip it is addressed to
+struct Bus([SimulatedDrv8830; 2]);
+
+impl ErrorType for Bus {
+    type Error = ErrorKind;
+}
+
+impl I2c for Bus {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        match self
+            .0
+            .iter_mut()
+            .find(|chip| chip.address().as_u8() == address)
+        {
+            Some(chip) => chip.transaction(address, operations),
+            None => Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+        }
+    }
+}
+
+fn main() -> Result<(), drv8830::Error<ErrorKind>> {
+    let bus = RefCell::new(Bus([
+        SimulatedDrv8830::new(LEFT),
+        SimulatedDrv8830::new(RIGHT),
+    ]));
+    let mut left = Drv8830::new(RefCellDevice::new(&bus), LEFT);
+    let mut right = Drv8830::new(RefCellDevice::new(&bus), RIGHT);
+
+    let vset = VSet::from_millivolts(3000, Rounding::Nearest)?;
+    left.forward(vset)?;
+    right.reverse(vset)?;
+    println!("left: {:?}", left.read_control()?.direction());
+    println!("right: {:?}", right.read_control()?.direction());
+
+    left.brake()?;
+    right.brake()?;
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use core::fmt;
+
+// State of a single address strapping pin (A0 or A1)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddressPin {
+    #[default]
+    Low,
+    Open,
+    High,
+}
+
+impl AddressPin {
+    const ALL: [Self; 3] = [Self::Low, Self::Open, Self::High];
+
+    fn index(self) -> u8 {
+        match self {
+            Self::Low => 0,
+            Self::Open => 1,
+            Self::High => 2,
+        }
+    }
+}
+
+// 7-bit I2C address of a DRV8830, determined by how A1 and A0 are strapped.
+// The nine valid addresses are 0x60 (both low) through 0x68 (both high).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Address {
+    a1: AddressPin,
+    a0: AddressPin,
+}
+
+impl Address {
+    const BASE: u8 = 0x60;
+
+    pub const fn new(a1: AddressPin, a0: AddressPin) -> Self {
+        Self { a1, a0 }
+    }
+
+    pub fn a1(self) -> AddressPin {
+        self.a1
+    }
+
+    pub fn a0(self) -> AddressPin {
+        self.a0
+    }
+
+    pub fn as_u8(self) -> u8 {
+        Self::BASE + self.a1.index() * 3 + self.a0.index()
+    }
+}
+
+impl From<Address> for u8 {
+    fn from(address: Address) -> Self {
+        address.as_u8()
+    }
+}
+
+impl TryFrom<u8> for Address {
+    type Error = InvalidAddress;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        let offset = value.wrapping_sub(Self::BASE);
+        if offset >= 9 {
+            return Err(InvalidAddress(value));
+        }
+        Ok(Self {
+            a1: AddressPin::ALL[usize::from(offset / 3)],
+            a0: AddressPin::ALL[usize::from(offset % 3)],
+        })
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#04x}", self.as_u8())
+    }
+}
+
+// Returned when a 7-bit address is not one the DRV8830 can be strapped to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidAddress(pub u8);
+
+impl fmt::Display for InvalidAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#04x} is not a valid DRV8830 address", self.0)
+    }
+}
+
+impl core::error::Error for InvalidAddress {}
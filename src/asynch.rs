@@ -0,0 +1,267 @@
+// Async counterparts of the register traits and driver, built on `embedded-hal-async`.
+// Register encoding is shared with the blocking path; only the bus access differs.
+use embedded_hal_async::i2c::I2c;
+
+use crate::config::DriverConfig;
+use crate::driver::q15_to_millivolts;
+#[cfg(feature = "float")]
+use crate::driver::speed_to_q15;
+use crate::shadow::Shadow;
+use crate::{
+    Address, Control, ControlReadback, ControlReg, CurrentLimit, Error, Fault, StopMode, VSet,
+};
+
+#[allow(async_fn_in_trait)]
+pub trait WriteRegister {
+    async fn write<I: I2c>(&self, i2c: &mut I, address: Address) -> Result<(), Error<I::Error>>;
+}
+#[allow(async_fn_in_trait)]
+pub trait ReadRegister {
+    async fn new<I: I2c>(i2c: &mut I, address: Address) -> Result<Self, Error<I::Error>>
+    where
+        Self: Sized;
+}
+
+async fn read_register<I: I2c>(
+    i2c: &mut I,
+    address: Address,
+    register: u8,
+) -> Result<u8, Error<I::Error>> {
+    let mut read_buf = [0u8; 1];
+    i2c.write_read(address.as_u8(), &[register], &mut read_buf)
+        .await
+        .map_err(Error::Bus)?;
+    Ok(read_buf[0])
+}
+
+impl WriteRegister for Control {
+    async fn write<I: I2c>(&self, i2c: &mut I, address: Address) -> Result<(), Error<I::Error>> {
+        i2c.write(address.as_u8(), &[Self::ADDRESS, self.encode()])
+            .await
+            .map_err(Error::Bus)
+    }
+}
+
+impl ReadRegister for ControlReadback {
+    async fn new<I: I2c>(i2c: &mut I, address: Address) -> Result<Self, Error<I::Error>>
+    where
+        Self: Sized,
+    {
+        let raw = read_register(i2c, address, Control::ADDRESS).await?;
+        Ok(ControlReg::from(raw).into())
+    }
+}
+
+impl ReadRegister for Fault {
+    async fn new<I: I2c>(i2c: &mut I, address: Address) -> Result<Self, Error<I::Error>>
+    where
+        Self: Sized,
+    {
+        Ok(Self::decode(
+            read_register(i2c, address, Self::ADDRESS).await?,
+        ))
+    }
+}
+impl WriteRegister for Fault {
+    async fn write<I: I2c>(&self, i2c: &mut I, address: Address) -> Result<(), Error<I::Error>> {
+        i2c.write(address.as_u8(), &[Self::ADDRESS, self.encode()])
+            .await
+            .map_err(Error::Bus)
+    }
+}
+
+// Async version of `crate::Drv8830`
+#[derive(Debug)]
+pub struct Drv8830<I> {
+    i2c: I,
+    address: Address,
+    config: DriverConfig,
+    shadow: Shadow,
+}
+
+impl<I: I2c> Drv8830<I> {
+    pub fn new(i2c: I, address: Address) -> Self {
+        Self {
+            i2c,
+            address,
+            config: DriverConfig::default(),
+            shadow: Shadow::default(),
+        }
+    }
+
+    // See `crate::Drv8830::with_current_limit`
+    pub fn with_current_limit(mut self, current_limit: CurrentLimit) -> Self {
+        self.config.current_limit = Some(current_limit);
+        self
+    }
+
+    pub fn current_limit(&self) -> Option<CurrentLimit> {
+        self.config.current_limit
+    }
+
+    pub fn with_stop_mode(mut self, stop_mode: StopMode) -> Self {
+        self.config.stop_mode = stop_mode;
+        self
+    }
+
+    pub fn set_stop_mode(&mut self, stop_mode: StopMode) {
+        self.config.stop_mode = stop_mode;
+    }
+
+    // See `crate::Drv8830::set_deadband`
+    pub fn set_deadband(&mut self, millivolts: u16) {
+        self.config.deadband_mv = millivolts;
+    }
+
+    // See `crate::Drv8830::with_verify`
+    pub fn with_verify(mut self, retries: u8) -> Self {
+        self.config.verify_retries = Some(retries);
+        self
+    }
+
+    pub fn set_verify(&mut self, retries: Option<u8>) {
+        self.config.verify_retries = retries;
+    }
+
+    // See `crate::Drv8830::with_cache`
+    pub fn with_cache(mut self) -> Self {
+        self.shadow.enabled = true;
+        self
+    }
+
+    pub fn set_cache(&mut self, enabled: bool) {
+        self.shadow.enabled = enabled;
+        self.shadow.invalidate();
+    }
+
+    pub fn invalidate_cache(&mut self) {
+        self.shadow.invalidate();
+    }
+
+    pub fn cached_control(&self) -> Option<Control> {
+        self.shadow.control()
+    }
+
+    pub fn cached_fault(&self) -> Option<Fault> {
+        self.shadow.fault()
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub async fn forward(&mut self, vset: VSet) -> Result<(), Error<I::Error>> {
+        self.write_control(Control {
+            vset,
+            ..Control::FORWARD
+        })
+        .await
+    }
+
+    pub async fn reverse(&mut self, vset: VSet) -> Result<(), Error<I::Error>> {
+        self.write_control(Control {
+            vset,
+            ..Control::REVERSE
+        })
+        .await
+    }
+
+    pub async fn coast(&mut self) -> Result<(), Error<I::Error>> {
+        self.write_control(Control::COAST).await
+    }
+
+    pub async fn brake(&mut self) -> Result<(), Error<I::Error>> {
+        self.write_control(Control::BRAKE).await
+    }
+
+    pub async fn stop(&mut self) -> Result<(), Error<I::Error>> {
+        self.write_control(self.config.stop_mode.into()).await
+    }
+
+    #[cfg(feature = "float")]
+    pub async fn set_speed(&mut self, speed: f32) -> Result<(), Error<I::Error>> {
+        self.set_speed_q15(speed_to_q15(speed)?).await
+    }
+
+    pub async fn set_speed_q15(&mut self, speed: i16) -> Result<(), Error<I::Error>> {
+        self.set_voltage_signed(q15_to_millivolts(speed)).await
+    }
+
+    pub async fn set_voltage_signed(&mut self, millivolts: i16) -> Result<(), Error<I::Error>> {
+        let control = self.config.signed_control(millivolts)?;
+        self.write_control(control).await
+    }
+
+    pub async fn write_control(&mut self, control: Control) -> Result<(), Error<I::Error>> {
+        if self.shadow.skip_control(control) {
+            return Ok(());
+        }
+        self.force_write(control).await
+    }
+
+    pub async fn force_write(&mut self, control: Control) -> Result<(), Error<I::Error>> {
+        let result = self.write_verified(control).await;
+        self.shadow.wrote_control(control, result)
+    }
+
+    async fn write_verified(&mut self, control: Control) -> Result<(), Error<I::Error>> {
+        if self.config.verify_retries.is_none() {
+            return control.write(&mut self.i2c, self.address).await;
+        }
+        let mut attempt = 0;
+        loop {
+            control.write(&mut self.i2c, self.address).await?;
+            let actual = read_register(&mut self.i2c, self.address, Control::ADDRESS).await?;
+            if self.config.verified(control.encode(), actual, attempt)? {
+                return Ok(());
+            }
+            attempt += 1;
+        }
+    }
+
+    pub async fn read_control(&mut self) -> Result<ControlReadback, Error<I::Error>> {
+        let result = read_register(&mut self.i2c, self.address, Control::ADDRESS).await;
+        self.shadow.read_control(result)
+    }
+
+    pub async fn read_fault(&mut self) -> Result<Fault, Error<I::Error>> {
+        let result = read_register(&mut self.i2c, self.address, Fault::ADDRESS).await;
+        self.shadow.read_fault(result)
+    }
+
+    pub async fn clear_faults(&mut self) -> Result<(), Error<I::Error>> {
+        let result = self
+            .i2c
+            .write(self.address.as_u8(), &[Fault::ADDRESS, Fault::CLEAR])
+            .await
+            .map_err(Error::Bus);
+        self.shadow.cleared(result)
+    }
+
+    pub async fn read_and_clear(&mut self) -> Result<Fault, Error<I::Error>> {
+        let mut read_buf = [0u8; 1];
+        let result = self
+            .i2c
+            .transaction(
+                self.address.as_u8(),
+                &mut Fault::read_and_clear_operations(&mut read_buf),
+            )
+            .await
+            .map_err(Error::Bus);
+        self.shadow.cleared(result)?;
+        Ok(Fault::decode(read_buf[0]))
+    }
+
+    // Fails with `Error::Fault` if the chip currently reports any fault
+    pub async fn check_fault(&mut self) -> Result<(), Error<I::Error>> {
+        let fault = self.read_fault().await?;
+        if fault.fault {
+            return Err(Error::Fault(fault));
+        }
+        Ok(())
+    }
+
+    pub fn release(self) -> I {
+        self.i2c
+    }
+}
@@ -0,0 +1,239 @@
+use core::ops::{Deref, DerefMut};
+use core::{array, fmt};
+
+use embedded_hal::i2c::I2c;
+
+use crate::config::DriverConfig;
+use crate::shadow::Shadow;
+use crate::{
+    Address, Control, CurrentLimit, Drv8830, Error, Fault, FaultEvents, FaultMonitor, StopMode,
+};
+
+// Two motors of a bank were given the same address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DuplicateAddress(pub Address);
+
+impl fmt::Display for DuplicateAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#04x} is used by more than one motor", self.0.as_u8())
+    }
+}
+
+impl core::error::Error for DuplicateAddress {}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    address: Address,
+    name: Option<&'static str>,
+    config: DriverConfig,
+    // Kept between drivers so the cache survives `motor` and the grouped operations
+    shadow: Shadow,
+}
+
+// One result per chip, in the order the bank was built with
+pub type BankResults<T, E, const N: usize> = [Result<T, Error<E>>; N];
+
+// Several DRV8830s sharing one bus. Grouped operations visit every chip and report
+// each result separately, so one chip failing does not stop the others.
+#[derive(Debug)]
+pub struct MotorBank<I, const N: usize> {
+    i2c: I,
+    motors: [Entry; N],
+}
+
+impl<I: I2c, const N: usize> MotorBank<I, N> {
+    // Fails if the same address is listed twice
+    pub fn new(i2c: I, addresses: [Address; N]) -> Result<Self, DuplicateAddress> {
+        Self::build(i2c, addresses.map(|address| (None, address)))
+    }
+
+    pub fn with_names(
+        i2c: I,
+        motors: [(&'static str, Address); N],
+    ) -> Result<Self, DuplicateAddress> {
+        Self::build(i2c, motors.map(|(name, address)| (Some(name), address)))
+    }
+
+    fn build(
+        i2c: I,
+        motors: [(Option<&'static str>, Address); N],
+    ) -> Result<Self, DuplicateAddress> {
+        for (i, (_, address)) in motors.iter().enumerate() {
+            if motors[..i].iter().any(|(_, other)| other == address) {
+                return Err(DuplicateAddress(*address));
+            }
+        }
+        Ok(Self {
+            i2c,
+            motors: motors.map(|(name, address)| Entry {
+                address,
+                name,
+                config: DriverConfig::default(),
+                shadow: Shadow::default(),
+            }),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    pub fn address(&self, index: usize) -> Option<Address> {
+        self.motors.get(index).map(|entry| entry.address)
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.motors
+            .iter()
+            .position(|entry| entry.name == Some(name))
+    }
+
+    // Per-motor settings, applied to every driver handed out by `motor` and so to the
+    // grouped operations too
+    pub fn set_stop_mode(&mut self, index: usize, stop_mode: StopMode) {
+        if let Some(entry) = self.motors.get_mut(index) {
+            entry.config.stop_mode = stop_mode;
+        }
+    }
+
+    pub fn set_current_limit(&mut self, index: usize, current_limit: CurrentLimit) {
+        if let Some(entry) = self.motors.get_mut(index) {
+            entry.config.current_limit = Some(current_limit);
+        }
+    }
+
+    // See `Drv8830::with_verify`
+    pub fn set_verify(&mut self, index: usize, retries: Option<u8>) {
+        if let Some(entry) = self.motors.get_mut(index) {
+            entry.config.verify_retries = retries;
+        }
+    }
+
+    // See `Drv8830::with_cache`. The cache is kept by the bank, so repeated grouped
+    // commands skip the writes that would not change anything.
+    pub fn set_cache(&mut self, index: usize, enabled: bool) {
+        if let Some(entry) = self.motors.get_mut(index) {
+            entry.shadow.enabled = enabled;
+            entry.shadow.invalidate();
+        }
+    }
+
+    // See `Drv8830::set_deadband`
+    pub fn set_deadband(&mut self, index: usize, millivolts: u16) {
+        if let Some(entry) = self.motors.get_mut(index) {
+            entry.config.deadband_mv = millivolts;
+        }
+    }
+
+    // Driver for one chip, borrowing the shared bus
+    pub fn motor(&mut self, index: usize) -> Option<BankMotor<'_, I>> {
+        let entry = self.motors.get_mut(index)?;
+        let driver = Drv8830::new(&mut self.i2c, entry.address)
+            .with_config(entry.config)
+            .with_shadow(entry.shadow);
+        Some(BankMotor {
+            driver,
+            shadow: &mut entry.shadow,
+        })
+    }
+
+    pub fn by_name(&mut self, name: &str) -> Option<BankMotor<'_, I>> {
+        let index = self.index_of(name)?;
+        self.motor(index)
+    }
+
+    pub fn write_all(&mut self, control: Control) -> BankResults<(), I::Error, N> {
+        self.each(|driver| driver.write_control(control))
+    }
+
+    pub fn brake_all(&mut self) -> BankResults<(), I::Error, N> {
+        self.each(|driver| driver.brake())
+    }
+
+    pub fn coast_all(&mut self) -> BankResults<(), I::Error, N> {
+        self.each(|driver| driver.coast())
+    }
+
+    // Stop each motor according to its own stop mode
+    pub fn stop_all(&mut self) -> BankResults<(), I::Error, N> {
+        self.each(|driver| driver.stop())
+    }
+
+    pub fn read_faults(&mut self) -> BankResults<Fault, I::Error, N> {
+        self.each(|driver| driver.read_fault())
+    }
+
+    pub fn clear_faults(&mut self) -> BankResults<(), I::Error, N> {
+        self.each(|driver| driver.clear_faults())
+    }
+
+    // Run one fault monitor per chip, `monitors[i]` belonging to motor `i`
+    pub fn poll_faults(
+        &mut self,
+        monitors: &mut [FaultMonitor; N],
+        now_us: u64,
+    ) -> BankResults<FaultEvents, I::Error, N> {
+        let mut monitors = monitors.iter_mut();
+        self.each(|driver| {
+            let monitor = monitors.next().expect("one monitor per motor");
+            monitor.poll(driver, now_us)
+        })
+    }
+
+    // Run `f` on every chip in turn
+    pub fn each<T>(
+        &mut self,
+        mut f: impl FnMut(&mut Drv8830<&mut I>) -> Result<T, Error<I::Error>>,
+    ) -> BankResults<T, I::Error, N> {
+        array::from_fn(|index| {
+            let mut driver = self.motor(index).expect("index is in range");
+            f(&mut driver)
+        })
+    }
+
+    // Worst-case total draw at the given output voltage, counting only the chips
+    // with a known current limit
+    pub fn max_power_mw(&self, output_millivolts: u16) -> u32 {
+        self.motors
+            .iter()
+            .filter_map(|entry| entry.config.current_limit)
+            .map(|limit| limit.max_power_mw(output_millivolts))
+            .sum()
+    }
+
+    pub fn release(self) -> I {
+        self.i2c
+    }
+}
+
+// Driver for one motor of a `MotorBank`, used through `Deref`. Hands its cache back to
+// the bank when dropped, so writes made through it are not repeated later.
+#[derive(Debug)]
+pub struct BankMotor<'a, I: I2c> {
+    driver: Drv8830<&'a mut I>,
+    shadow: &'a mut Shadow,
+}
+
+impl<'a, I: I2c> Deref for BankMotor<'a, I> {
+    type Target = Drv8830<&'a mut I>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.driver
+    }
+}
+
+impl<I: I2c> DerefMut for BankMotor<'_, I> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.driver
+    }
+}
+
+impl<I: I2c> Drop for BankMotor<'_, I> {
+    fn drop(&mut self) {
+        *self.shadow = self.driver.shadow();
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{Control, CurrentLimit, Error, InvalidVoltage, StopMode, VSet};
+
+// Bus-independent settings and decisions, shared by the blocking and async drivers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DriverConfig {
+    // Readback retries after a CONTROL write, `None` when verification is off
+    pub(crate) verify_retries: Option<u8>,
+    // Used by the signed-speed API for zero and anything inside the deadband
+    pub(crate) stop_mode: StopMode,
+    pub(crate) deadband_mv: u16,
+    pub(crate) current_limit: Option<CurrentLimit>,
+}
+
+impl Default for DriverConfig {
+    fn default() -> Self {
+        Self {
+            verify_retries: None,
+            stop_mode: StopMode::Coast,
+            deadband_mv: VSet::MIN_MILLIVOLTS,
+            current_limit: None,
+        }
+    }
+}
+
+impl DriverConfig {
+    // Outcome of reading CONTROL back after the `attempt`th write: true if it took,
+    // false to write again, or the mismatch once the retries are used up
+    pub(crate) fn verified<E>(
+        &self,
+        expected: u8,
+        actual: u8,
+        attempt: u8,
+    ) -> Result<bool, Error<E>> {
+        if actual == expected {
+            return Ok(true);
+        }
+        if attempt >= self.verify_retries.unwrap_or(0) {
+            return Err(Error::VerifyMismatch { expected, actual });
+        }
+        Ok(false)
+    }
+
+    // CONTROL value for a signed output voltage in millivolts
+    pub(crate) fn signed_control(&self, millivolts: i16) -> Result<Control, InvalidVoltage> {
+        let magnitude = millivolts.unsigned_abs();
+        if magnitude > VSet::MAX_MILLIVOLTS {
+            return Err(InvalidVoltage);
+        }
+        if magnitude < self.deadband_mv {
+            return Ok(self.stop_mode.into());
+        }
+        let magnitude = magnitude.max(VSet::MIN_MILLIVOLTS) as i16;
+        Control::from_signed_millivolts(magnitude * millivolts.signum(), self.stop_mode)
+    }
+}
@@ -0,0 +1,63 @@
+// E24 preferred values, one decade
+const E24: [u32; 24] = [
+    10, 11, 12, 13, 15, 16, 18, 20, 22, 24, 27, 30, 33, 36, 39, 43, 47, 51, 56, 62, 68, 75, 82, 91,
+];
+
+// Current limit set by the resistor on ISENSE: the chip regulates the motor current
+// to 200 mV / RSENSE and raises ILIMIT if it has to do so for more than 275 ms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CurrentLimit {
+    rsense_milliohms: u32,
+}
+
+impl CurrentLimit {
+    pub const SENSE_MILLIVOLTS: u32 = 200;
+
+    // `None` for a zero resistance, which would mean no limit at all
+    pub const fn from_rsense_milliohms(rsense_milliohms: u32) -> Option<Self> {
+        if rsense_milliohms == 0 {
+            return None;
+        }
+        Some(Self { rsense_milliohms })
+    }
+
+    // Smallest E24 resistor keeping the limit at or below `target_ma`, but
+    // never below 10 mΩ, so targets above 20 A give a 20 A limit
+    pub fn for_target_ma(target_ma: u32) -> Option<Self> {
+        let exact = Self::exact_rsense_milliohms(target_ma)?;
+        let mut decade = 1;
+        loop {
+            if let Some(&value) = E24.iter().find(|&&value| value * decade >= exact) {
+                return Self::from_rsense_milliohms(value * decade);
+            }
+            decade *= 10;
+        }
+    }
+
+    // Resistance giving exactly `target_ma`, rounded up so the limit never exceeds it
+    pub fn exact_rsense_milliohms(target_ma: u32) -> Option<u32> {
+        if target_ma == 0 {
+            return None;
+        }
+        Some((Self::SENSE_MILLIVOLTS * 1000).div_ceil(target_ma))
+    }
+
+    pub fn rsense_milliohms(self) -> u32 {
+        self.rsense_milliohms
+    }
+
+    pub fn limit_ma(self) -> u32 {
+        Self::SENSE_MILLIVOLTS * 1000 / self.rsense_milliohms
+    }
+
+    #[cfg(feature = "float")]
+    pub fn limit_amps(self) -> f32 {
+        Self::SENSE_MILLIVOLTS as f32 / self.rsense_milliohms as f32
+    }
+
+    // Most power the motor can draw at the given output voltage, for budgeting a
+    // supply shared by several motors
+    pub fn max_power_mw(self, output_millivolts: u16) -> u32 {
+        self.limit_ma() * u32::from(output_millivolts) / 1000
+    }
+}
@@ -0,0 +1,173 @@
+use embedded_hal::i2c::I2c;
+
+#[cfg(feature = "float")]
+use crate::driver::speed_to_q15;
+use crate::{Drv8830, Error};
+
+// Full scale of the mixed outputs, matching the Q15 speed API
+pub(crate) const FULL_SCALE: i32 = 1 << 15;
+
+// Wheel spacing and top speed, used to turn velocities into per-side speeds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DriveGeometry {
+    // Distance between the left and right wheel contact points
+    pub track_width_mm: u32,
+    // Wheel surface speed at full output voltage
+    pub max_speed_mm_per_s: u32,
+}
+
+// Per-side output adjustment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Side {
+    pub(crate) inverted: bool,
+    pub(crate) trim_permille: u16,
+}
+
+impl Default for Side {
+    fn default() -> Self {
+        Self {
+            inverted: false,
+            trim_permille: 1000,
+        }
+    }
+}
+
+impl Side {
+    pub(crate) fn apply(self, speed: i32) -> i16 {
+        let speed = speed * i32::from(self.trim_permille) / 1000;
+        let speed = if self.inverted { -speed } else { speed };
+        speed.clamp(-FULL_SCALE, FULL_SCALE - 1) as i16
+    }
+}
+
+// Left/right pair of motors driven as one differential (tank) base
+#[derive(Debug)]
+pub struct DifferentialDrive<I> {
+    left: Drv8830<I>,
+    right: Drv8830<I>,
+    geometry: DriveGeometry,
+    left_side: Side,
+    right_side: Side,
+}
+
+impl<I: I2c> DifferentialDrive<I> {
+    pub fn new(left: Drv8830<I>, right: Drv8830<I>, geometry: DriveGeometry) -> Self {
+        Self {
+            left,
+            right,
+            geometry,
+            left_side: Side::default(),
+            right_side: Side::default(),
+        }
+    }
+
+    // Flip a side whose motor is mounted mirrored, so positive is forward on both
+    pub fn with_inverted(mut self, left: bool, right: bool) -> Self {
+        self.left_side.inverted = left;
+        self.right_side.inverted = right;
+        self
+    }
+
+    // Scale each side's output to match mismatched motors, 1000 being unity. Applied
+    // after mixing, so a trim above 1000 can clip at full scale.
+    pub fn with_trim(mut self, left_permille: u16, right_permille: u16) -> Self {
+        self.left_side.trim_permille = left_permille;
+        self.right_side.trim_permille = right_permille;
+        self
+    }
+
+    pub fn geometry(&self) -> DriveGeometry {
+        self.geometry
+    }
+
+    pub fn set_geometry(&mut self, geometry: DriveGeometry) {
+        self.geometry = geometry;
+    }
+
+    // Arcade inputs as Q15 fractions of full scale: positive throttle drives forward,
+    // positive turn turns clockwise (left side faster)
+    pub fn arcade_q15(&mut self, throttle: i16, turn: i16) -> Result<(), Error<I::Error>> {
+        let throttle = i32::from(throttle);
+        let turn = i32::from(turn);
+        self.drive_mixed(throttle + turn, throttle - turn)
+    }
+
+    #[cfg(feature = "float")]
+    pub fn arcade(&mut self, throttle: f32, turn: f32) -> Result<(), Error<I::Error>> {
+        self.arcade_q15(speed_to_q15(throttle)?, speed_to_q15(turn)?)
+    }
+
+    // Body velocity: forward speed and counter-clockwise yaw rate
+    pub fn set_velocity(
+        &mut self,
+        linear_mm_per_s: i32,
+        angular_mrad_per_s: i32,
+    ) -> Result<(), Error<I::Error>> {
+        let linear = i64::from(linear_mm_per_s);
+        // Each side's offset from the centre speed: omega * track / 2
+        let turn = i64::from(angular_mrad_per_s) * i64::from(self.geometry.track_width_mm) / 2000;
+        let left = to_scale(linear - turn, self.geometry.max_speed_mm_per_s);
+        let right = to_scale(linear + turn, self.geometry.max_speed_mm_per_s);
+        self.drive_mixed(left, right)
+    }
+
+    #[cfg(feature = "float")]
+    pub fn set_twist(
+        &mut self,
+        linear_m_per_s: f32,
+        angular_rad_per_s: f32,
+    ) -> Result<(), Error<I::Error>> {
+        self.set_velocity(
+            (linear_m_per_s * 1000.0) as i32,
+            (angular_rad_per_s * 1000.0) as i32,
+        )
+    }
+
+    pub fn stop(&mut self) -> Result<(), Error<I::Error>> {
+        let left = self.left.stop();
+        let right = self.right.stop();
+        left.and(right)
+    }
+
+    pub fn left(&mut self) -> &mut Drv8830<I> {
+        &mut self.left
+    }
+
+    pub fn right(&mut self) -> &mut Drv8830<I> {
+        &mut self.right
+    }
+
+    pub fn release(self) -> (Drv8830<I>, Drv8830<I>) {
+        (self.left, self.right)
+    }
+
+    // Both writes are attempted even if the first fails, so one side is never left
+    // running on its old command; the first error is returned
+    fn drive_mixed(&mut self, left: i32, right: i32) -> Result<(), Error<I::Error>> {
+        let [left, right] = normalize([left, right]);
+        let left = self.left.set_speed_q15(self.left_side.apply(left));
+        let right = self.right.set_speed_q15(self.right_side.apply(right));
+        left.and(right)
+    }
+}
+
+// Wheel speed in mm/s to the Q15 scale, saturating far outside the range
+pub(crate) fn to_scale(speed_mm_per_s: i64, max_speed_mm_per_s: u32) -> i32 {
+    let max = i64::from(max_speed_mm_per_s.max(1));
+    (speed_mm_per_s * FULL_SCALE as i64 / max).clamp(i64::from(i32::MIN), i64::from(i32::MAX))
+        as i32
+}
+
+// Scale every speed down by the same factor if any exceeds full scale, keeping their
+// ratios (and so the direction of travel)
+pub(crate) fn normalize<const N: usize>(speeds: [i32; N]) -> [i32; N] {
+    let peak = speeds
+        .iter()
+        .map(|speed| i64::from(*speed).abs())
+        .max()
+        .unwrap_or(0);
+    if peak <= FULL_SCALE as i64 {
+        return speeds;
+    }
+    speeds.map(|speed| (i64::from(speed) * FULL_SCALE as i64 / peak) as i32)
+}
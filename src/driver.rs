@@ -0,0 +1,242 @@
+use embedded_hal::i2c::I2c;
+
+use crate::config::DriverConfig;
+use crate::shadow::Shadow;
+#[cfg(feature = "float")]
+use crate::InvalidVoltage;
+use crate::{
+    read_register, Address, Control, ControlReadback, CurrentLimit, Error, Fault, StopMode, VSet,
+    WriteRegister,
+};
+
+// High-level handle for a single DRV8830 that keeps the bus and chip address together.
+// Pass `&mut bus` instead of the bus itself to borrow it rather than take ownership, or
+// an `embedded-hal-bus` device (`RefCellDevice`, `CriticalSectionDevice`, `AtomicDevice`)
+// to share the bus with other drivers.
+#[derive(Debug)]
+pub struct Drv8830<I> {
+    i2c: I,
+    address: Address,
+    config: DriverConfig,
+    shadow: Shadow,
+}
+
+impl<I: I2c> Drv8830<I> {
+    pub fn new(i2c: I, address: Address) -> Self {
+        Self {
+            i2c,
+            address,
+            config: DriverConfig::default(),
+            shadow: Shadow::default(),
+        }
+    }
+
+    // Record the ISENSE resistor fitted to this chip, for fault reporting and power
+    // budgeting. The chip itself needs no configuration.
+    pub fn with_current_limit(mut self, current_limit: CurrentLimit) -> Self {
+        self.config.current_limit = Some(current_limit);
+        self
+    }
+
+    // Settings kept per motor by `MotorBank`
+    pub(crate) fn with_config(mut self, config: DriverConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub(crate) fn with_shadow(mut self, shadow: Shadow) -> Self {
+        self.shadow = shadow;
+        self
+    }
+
+    pub(crate) fn shadow(&self) -> Shadow {
+        self.shadow
+    }
+
+    pub fn current_limit(&self) -> Option<CurrentLimit> {
+        self.config.current_limit
+    }
+
+    pub fn with_stop_mode(mut self, stop_mode: StopMode) -> Self {
+        self.config.stop_mode = stop_mode;
+        self
+    }
+
+    pub fn set_stop_mode(&mut self, stop_mode: StopMode) {
+        self.config.stop_mode = stop_mode;
+    }
+
+    // Signed commands with a smaller magnitude than this stop the motor. Defaults to the
+    // 0.48 V minimum output; anything between a lower deadband and 0.48 V is raised to it.
+    pub fn set_deadband(&mut self, millivolts: u16) {
+        self.config.deadband_mv = millivolts;
+    }
+
+    // Read CONTROL back after every write and rewrite it up to `retries` more times
+    // on a mismatch before giving up with `Error::VerifyMismatch`
+    pub fn with_verify(mut self, retries: u8) -> Self {
+        self.config.verify_retries = Some(retries);
+        self
+    }
+
+    pub fn set_verify(&mut self, retries: Option<u8>) {
+        self.config.verify_retries = retries;
+    }
+
+    // Keep a shadow copy of CONTROL and FAULT and skip writes that would not change
+    // the register. Use `force_write` or `invalidate_cache` after a chip reset.
+    pub fn with_cache(mut self) -> Self {
+        self.shadow.enabled = true;
+        self
+    }
+
+    pub fn set_cache(&mut self, enabled: bool) {
+        self.shadow.enabled = enabled;
+        self.shadow.invalidate();
+    }
+
+    pub fn invalidate_cache(&mut self) {
+        self.shadow.invalidate();
+    }
+
+    // Last CONTROL value written or read, if the cache is enabled and valid
+    pub fn cached_control(&self) -> Option<Control> {
+        self.shadow.control()
+    }
+
+    pub fn cached_fault(&self) -> Option<Fault> {
+        self.shadow.fault()
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub fn forward(&mut self, vset: VSet) -> Result<(), Error<I::Error>> {
+        self.write_control(Control {
+            vset,
+            ..Control::FORWARD
+        })
+    }
+
+    pub fn reverse(&mut self, vset: VSet) -> Result<(), Error<I::Error>> {
+        self.write_control(Control {
+            vset,
+            ..Control::REVERSE
+        })
+    }
+
+    pub fn coast(&mut self) -> Result<(), Error<I::Error>> {
+        self.write_control(Control::COAST)
+    }
+
+    pub fn brake(&mut self) -> Result<(), Error<I::Error>> {
+        self.write_control(Control::BRAKE)
+    }
+
+    // Stop according to the configured `StopMode`
+    pub fn stop(&mut self) -> Result<(), Error<I::Error>> {
+        self.write_control(self.config.stop_mode.into())
+    }
+
+    // Signed speed as a fraction of full scale, -1.0 (full reverse) to 1.0 (full forward)
+    #[cfg(feature = "float")]
+    pub fn set_speed(&mut self, speed: f32) -> Result<(), Error<I::Error>> {
+        self.set_speed_q15(speed_to_q15(speed)?)
+    }
+
+    // Signed speed as a Q15 fraction of full scale, where `i16::MIN` is full reverse and
+    // `i16::MAX` full forward. Encodes identically to `set_speed`.
+    pub fn set_speed_q15(&mut self, speed: i16) -> Result<(), Error<I::Error>> {
+        self.set_voltage_signed(q15_to_millivolts(speed))
+    }
+
+    // Signed output voltage in millivolts, -5060 to 5060
+    pub fn set_voltage_signed(&mut self, millivolts: i16) -> Result<(), Error<I::Error>> {
+        let control = self.config.signed_control(millivolts)?;
+        self.write_control(control)
+    }
+
+    pub fn write_control(&mut self, control: Control) -> Result<(), Error<I::Error>> {
+        if self.shadow.skip_control(control) {
+            return Ok(());
+        }
+        self.force_write(control)
+    }
+
+    // Write CONTROL even if the cache says it already holds `control`
+    pub fn force_write(&mut self, control: Control) -> Result<(), Error<I::Error>> {
+        let result = self.write_verified(control);
+        self.shadow.wrote_control(control, result)
+    }
+
+    fn write_verified(&mut self, control: Control) -> Result<(), Error<I::Error>> {
+        if self.config.verify_retries.is_none() {
+            return control.write(&mut self.i2c, self.address);
+        }
+        let mut attempt = 0;
+        loop {
+            control.write(&mut self.i2c, self.address)?;
+            let actual = read_register(&mut self.i2c, self.address, Control::ADDRESS)?;
+            if self.config.verified(control.encode(), actual, attempt)? {
+                return Ok(());
+            }
+            attempt += 1;
+        }
+    }
+
+    // Current CONTROL register contents, e.g. to reconcile state after a reset. Never
+    // fails on the reserved VSET codes the chip powers up with.
+    pub fn read_control(&mut self) -> Result<ControlReadback, Error<I::Error>> {
+        let result = read_register(&mut self.i2c, self.address, Control::ADDRESS);
+        self.shadow.read_control(result)
+    }
+
+    pub fn read_fault(&mut self) -> Result<Fault, Error<I::Error>> {
+        let result = read_register(&mut self.i2c, self.address, Fault::ADDRESS);
+        self.shadow.read_fault(result)
+    }
+
+    // Clear the fault status, writing nothing but the CLEAR bit
+    pub fn clear_faults(&mut self) -> Result<(), Error<I::Error>> {
+        let result = Fault::clear_faults(&mut self.i2c, self.address);
+        self.shadow.cleared(result)
+    }
+
+    // Snapshot FAULT and clear it in one transaction, see `Fault::read_and_clear`
+    pub fn read_and_clear(&mut self) -> Result<Fault, Error<I::Error>> {
+        let result = Fault::read_and_clear(&mut self.i2c, self.address);
+        self.shadow.cleared(result)
+    }
+
+    // Fails with `Error::Fault` if the chip currently reports any fault
+    pub fn check_fault(&mut self) -> Result<(), Error<I::Error>> {
+        let fault = self.read_fault()?;
+        if fault.fault {
+            return Err(Error::Fault(fault));
+        }
+        Ok(())
+    }
+
+    // Give back the bus so it can be reused or dropped
+    pub fn release(self) -> I {
+        self.i2c
+    }
+}
+
+// The float API is converted to Q15 first so both paths share the integer encoding
+#[cfg(feature = "float")]
+pub(crate) fn speed_to_q15(speed: f32) -> Result<i16, InvalidVoltage> {
+    // Also rejects NaN
+    if !(-1.0..=1.0).contains(&speed) {
+        return Err(InvalidVoltage);
+    }
+    // Saturates 1.0 to 0x7FFF
+    Ok((speed * 32768.0 + 0.5f32.copysign(speed)) as i16)
+}
+
+pub(crate) fn q15_to_millivolts(speed: i16) -> i16 {
+    let scaled = i32::from(speed) * i32::from(VSet::MAX_MILLIVOLTS);
+    // Round half away from zero
+    ((scaled + 16384 * scaled.signum()) / 32768) as i16
+}
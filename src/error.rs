@@ -0,0 +1,86 @@
+use core::fmt;
+
+use embedded_hal::i2c::{self, ErrorKind};
+
+use crate::{Fault, InvalidAddress, InvalidVoltage};
+
+// Errors returned by the driver, wrapping the underlying I2C bus error `E`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E> {
+    // The I2C transaction itself failed
+    Bus(E),
+    // Requested voltage (or VSET code) is outside what the chip can output
+    InvalidVoltage,
+    // The 7-bit address is not one the chip can be strapped to
+    InvalidAddress(u8),
+    // The chip reported a fault condition
+    Fault(Fault),
+    // Reading back a register did not return what was written
+    VerifyMismatch { expected: u8, actual: u8 },
+}
+
+impl<E> From<InvalidVoltage> for Error<E> {
+    fn from(_: InvalidVoltage) -> Self {
+        Self::InvalidVoltage
+    }
+}
+
+impl<E> From<InvalidAddress> for Error<E> {
+    fn from(InvalidAddress(address): InvalidAddress) -> Self {
+        Self::InvalidAddress(address)
+    }
+}
+
+// Lets `Error` stand in wherever an I2C error is expected; driver-level errors map to `Other`
+impl<E: i2c::Error> i2c::Error for Error<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Bus(e) => e.kind(),
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+impl<E: fmt::Debug> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bus(e) => write!(f, "I2C bus error: {e:?}"),
+            Self::InvalidVoltage => fmt::Display::fmt(&InvalidVoltage, f),
+            Self::InvalidAddress(address) => fmt::Display::fmt(&InvalidAddress(*address), f),
+            Self::Fault(fault) => write!(f, "DRV8830 reported a fault: {fault:?}"),
+            Self::VerifyMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "register readback mismatch: wrote {expected:#04x}, read {actual:#04x}"
+                )
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug> core::error::Error for Error<E> {}
+
+// Errors from the closed-loop controllers: either the driver failed, or reading the
+// feedback sensor did (`F`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlError<E, F> {
+    Driver(Error<E>),
+    Feedback(F),
+}
+
+impl<E, F> From<Error<E>> for ControlError<E, F> {
+    fn from(error: Error<E>) -> Self {
+        Self::Driver(error)
+    }
+}
+
+impl<E: fmt::Debug, F: fmt::Debug> fmt::Display for ControlError<E, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Driver(e) => fmt::Display::fmt(e, f),
+            Self::Feedback(e) => write!(f, "feedback sensor error: {e:?}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug, F: fmt::Debug> core::error::Error for ControlError<E, F> {}
@@ -1,62 +1,261 @@
 #![no_std]
-use embedded_hal::i2c::I2c;
+use embedded_hal::i2c::{I2c, Operation};
+
+mod address;
+mod bank;
+mod config;
+mod current;
+mod drive;
+#[cfg(feature = "async")]
+pub mod asynch;
+mod driver;
+mod error;
+mod mecanum;
+mod monitor;
+#[cfg(feature = "float")]
+mod pid;
+mod ramp;
+mod register;
+#[cfg(feature = "float")]
+mod servo;
+mod shadow;
+#[cfg(feature = "float")]
+mod speed;
+#[cfg(feature = "sim")]
+pub mod sim;
+mod stepper;
+mod vset;
+
+pub use address::{Address, AddressPin, InvalidAddress};
+pub use bank::{BankMotor, BankResults, DuplicateAddress, MotorBank};
+pub use current::CurrentLimit;
+pub use drive::{DifferentialDrive, DriveGeometry};
+pub use driver::Drv8830;
+pub use error::{ControlError, Error};
+pub use mecanum::{MecanumDrive, MecanumGeometry};
+pub use monitor::{FaultEvent, FaultEvents, FaultKind, FaultMonitor, FaultPolicies, FaultPolicy};
+#[cfg(feature = "float")]
+pub use pid::{Pid, PidGains};
+pub use ramp::{Profile, Ramp, RampConfig, ReversalPolicy};
+pub use register::{ControlReg, FaultReg};
+#[cfg(feature = "float")]
+pub use servo::{
+    AnalogFeedback, EncoderFeedback, PositionController, PositionFeedback, ServoConfig, ServoStatus,
+};
+#[cfg(feature = "float")]
+pub use speed::{Encoder, EncoderReading, FeedForward, SpeedController};
+pub use stepper::{StepDirection, StepMode, Stepper, StepperConfig};
+pub use vset::{InvalidVoltage, Rounding, VSet};
 
 pub trait WriteRegister {
     // #[cfg(feature = "rpi")]
     // fn write(&self, i2c: &mut I2c) -> Result<()>;
-    fn write<I: I2c>(&self, i2c: &mut I, chip_addr: u8) -> Result<(), I::Error>;
+    fn write<I: I2c>(&self, i2c: &mut I, address: Address) -> Result<(), Error<I::Error>>;
 }
 pub trait ReadRegister {
 
-    fn new<I: I2c>(i2c: &mut I, chip_addr: u8) -> Result<Self, I::Error>
+    fn new<I: I2c>(i2c: &mut I, address: Address) -> Result<Self, Error<I::Error>>
     where
         Self: Sized;
 }
 
-#[derive(Debug, Default)]
+pub(crate) fn read_register<I: I2c>(
+    i2c: &mut I,
+    address: Address,
+    register: u8,
+) -> Result<u8, Error<I::Error>> {
+    let mut read_buf = [0u8; 1];
+    i2c.write_read(address.as_u8(), &[register], &mut read_buf)
+        .map_err(Error::Bus)?;
+    Ok(read_buf[0])
+}
+
+// H-bridge state selected by the IN1/IN2 bits
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    #[default]
+    Coast,
+    Reverse,
+    Forward,
+    Brake,
+}
+impl Direction {
+    // Shared by `Control` and `ControlReadback`
+    pub(crate) const fn from_inputs(in1: bool, in2: bool) -> Self {
+        match (in1, in2) {
+            (false, false) => Self::Coast,
+            (false, true) => Self::Reverse,
+            (true, false) => Self::Forward,
+            (true, true) => Self::Brake,
+        }
+    }
+}
+
+// How to hold the motor when it is not being driven
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StopMode {
+    #[default]
+    Coast,
+    Brake,
+}
+
+impl From<StopMode> for Control {
+    fn from(stop: StopMode) -> Self {
+        match stop {
+            StopMode::Coast => Control::COAST,
+            StopMode::Brake => Control::BRAKE,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Control {
     in1: bool,
     in2: bool,
-    // Output voltage in volts that the driver will attempt to match (1.29V - 5.06V)
-    pub speed_mult: f32,
+    // Output voltage the driver will regulate to (0.48V - 5.06V)
+    pub vset: VSet,
 }
 impl Control {
-    const ADDRESS: u8 = 0x00;
-    const MAX_VOLTAGE: f32 = 5.06;
-    const MIN_VOLTAGE: f32 = 0.8;
+    const ADDRESS: u8 = ControlReg::ADDRESS;
     pub const COAST: Self = Self {
         in1: false,
         in2: false,
-        speed_mult: 1.0,
+        vset: VSet::MAX,
     };
     pub const REVERSE: Self = Self {
         in1: false,
         in2: true,
-        speed_mult: 1.0,
+        vset: VSet::MAX,
     };
     pub const FORWARD: Self = Self {
         in1: true,
         in2: false,
-        speed_mult: 1.0,
+        vset: VSet::MAX,
     };
     pub const BRAKE: Self = Self {
         in1: true,
         in2: true,
-        speed_mult: 1.0,
+        vset: VSet::MAX,
     };
+
+    pub const fn from_direction(direction: Direction, vset: VSet) -> Self {
+        let (in1, in2) = match direction {
+            Direction::Coast => (false, false),
+            Direction::Reverse => (false, true),
+            Direction::Forward => (true, false),
+            Direction::Brake => (true, true),
+        };
+        Self { in1, in2, vset }
+    }
+
+    // Positive millivolts drive forward and negative in reverse. Magnitudes below the
+    // chip's 0.48 V minimum output give `stop`, magnitudes above 5.06 V are rejected.
+    pub fn from_signed_millivolts(millivolts: i16, stop: StopMode) -> Result<Self, InvalidVoltage> {
+        let magnitude = millivolts.unsigned_abs();
+        if magnitude < VSet::MIN_MILLIVOLTS {
+            return Ok(stop.into());
+        }
+        let direction = if millivolts > 0 {
+            Direction::Forward
+        } else {
+            Direction::Reverse
+        };
+        let vset = VSet::from_millivolts(magnitude, Rounding::Nearest)?;
+        Ok(Self::from_direction(direction, vset))
+    }
+
+    pub const fn direction(&self) -> Direction {
+        Direction::from_inputs(self.in1, self.in2)
+    }
+
+    // Register encoding shared by the blocking and async paths
+    pub(crate) fn encode(&self) -> u8 {
+        ControlReg::from(*self).into()
+    }
+
+    // Fails on the reserved VSET codes 0x00-0x05; use `ControlReadback` for values
+    // read from the chip
+    pub(crate) fn decode(read_buf: u8) -> Result<Self, InvalidVoltage> {
+        ControlReg::from(read_buf).try_into()
+    }
+}
+impl From<Control> for ControlReg {
+    fn from(control: Control) -> Self {
+        let mut reg = ControlReg::default();
+        reg.set_vset_code(control.vset.code());
+        reg.set_in2(control.in2);
+        reg.set_in1(control.in1);
+        reg
+    }
+}
+impl TryFrom<ControlReg> for Control {
+    type Error = InvalidVoltage;
+
+    fn try_from(reg: ControlReg) -> Result<Self, Self::Error> {
+        Ok(Self {
+            in1: reg.in1(),
+            in2: reg.in2(),
+            vset: VSet::from_code(reg.vset_code())?,
+        })
+    }
 }
 impl WriteRegister for Control {
-    fn write<I: I2c>(&self, i2c: &mut I, chip_addr: u8) -> Result<(), I::Error> {
-        // VOUT = 4 x VREF x (VSET +1) / 64, where VREF is the internal 1.285-V
-        let vout = (Self::MAX_VOLTAGE - Self::MIN_VOLTAGE) * self.speed_mult.clamp(0.0, 100.0) + Self::MIN_VOLTAGE;
-        let voltage_enc = (vout / 0.0803) as u8;
-        let write_reg = (voltage_enc << 2) | (u8::from(self.in2) << 1) | u8::from(self.in1);
-        i2c.write(chip_addr, &[Self::ADDRESS, write_reg])?;
+    fn write<I: I2c>(&self, i2c: &mut I, address: Address) -> Result<(), Error<I::Error>> {
+        i2c.write(address.as_u8(), &[Self::ADDRESS, self.encode()])
+            .map_err(Error::Bus)?;
         Ok(())
     }
 }
 
-#[derive(Debug, Default)]
+// CONTROL as read back from the chip. Unlike `Control` it also represents the reserved
+// VSET codes 0x00-0x05, which the register holds after power-up or a brownout.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ControlReadback(ControlReg);
+impl ControlReadback {
+    pub fn direction(&self) -> Direction {
+        Direction::from_inputs(self.0.in1(), self.0.in2())
+    }
+
+    // `None` for a reserved VSET code
+    pub fn vset(&self) -> Option<VSet> {
+        VSet::from_code(self.0.vset_code()).ok()
+    }
+
+    // The equivalent command, if VSET holds a valid code
+    pub fn control(&self) -> Option<Control> {
+        Control::try_from(self.0).ok()
+    }
+
+    pub const fn raw(&self) -> ControlReg {
+        self.0
+    }
+}
+impl From<ControlReg> for ControlReadback {
+    fn from(reg: ControlReg) -> Self {
+        Self(reg)
+    }
+}
+impl From<Control> for ControlReadback {
+    fn from(control: Control) -> Self {
+        Self(control.into())
+    }
+}
+impl PartialEq<Control> for ControlReadback {
+    fn eq(&self, other: &Control) -> bool {
+        self.0 == ControlReg::from(*other)
+    }
+}
+impl ReadRegister for ControlReadback {
+    fn new<I: I2c>(i2c: &mut I, address: Address) -> Result<Self, Error<I::Error>>
+    where
+        Self: Sized,
+    {
+        let raw = read_register(i2c, address, Control::ADDRESS)?;
+        Ok(Self(raw.into()))
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Fault {
     // When written to 1, clears the fault status bits
     pub clear: bool,
@@ -72,37 +271,86 @@ pub struct Fault {
     pub fault: bool,
 }
 impl Fault {
-    const ADDRESS: u8 = 0x01;
+    const ADDRESS: u8 = FaultReg::ADDRESS;
+    const CLEAR: u8 = FaultReg::CLEAR_ONLY.bits();
+
+    // Write only the CLEAR bit
+    pub fn clear_faults<I: I2c>(i2c: &mut I, address: Address) -> Result<(), Error<I::Error>> {
+        i2c.write(address.as_u8(), &[Self::ADDRESS, Self::CLEAR])
+            .map_err(Error::Bus)
+    }
+
+    // Read FAULT and clear it within a single transaction (repeated start, no stop in
+    // between) so a fault latching between the two is not lost unseen.
+    pub fn read_and_clear<I: I2c>(i2c: &mut I, address: Address) -> Result<Self, Error<I::Error>> {
+        let mut read_buf = [0u8; 1];
+        i2c.transaction(
+            address.as_u8(),
+            &mut Self::read_and_clear_operations(&mut read_buf),
+        )
+        .map_err(Error::Bus)?;
+        Ok(Self::decode(read_buf[0]))
+    }
+
+    // Shared with the async driver, whose `Operation` is the same type
+    pub(crate) fn read_and_clear_operations(read_buf: &mut [u8; 1]) -> [Operation<'_>; 3] {
+        [
+            Operation::Write(&[Self::ADDRESS]),
+            Operation::Read(read_buf),
+            Operation::Write(&[Self::ADDRESS, Self::CLEAR]),
+        ]
+    }
+
+    pub(crate) fn decode(read_buf: u8) -> Self {
+        FaultReg::from(read_buf).into()
+    }
+
+    // Value for a FAULT write: only CLEAR is writable, so the read-only status
+    // bits are never echoed back
+    pub(crate) fn encode(&self) -> u8 {
+        let mut reg = FaultReg::default();
+        reg.set_clear(self.clear);
+        reg.into()
+    }
+}
+impl From<FaultReg> for Fault {
+    fn from(reg: FaultReg) -> Self {
+        Self {
+            clear: reg.clear(),
+            i_limit: reg.i_limit(),
+            ots: reg.ots(),
+            uvlo: reg.uvlo(),
+            ocp: reg.ocp(),
+            fault: reg.fault(),
+        }
+    }
+}
+impl From<Fault> for FaultReg {
+    fn from(fault: Fault) -> Self {
+        let mut reg = FaultReg::default();
+        reg.set_clear(fault.clear);
+        reg.set_i_limit(fault.i_limit);
+        reg.set_ots(fault.ots);
+        reg.set_uvlo(fault.uvlo);
+        reg.set_ocp(fault.ocp);
+        reg.set_fault(fault.fault);
+        reg
+    }
 }
 impl ReadRegister for Fault {
 
-    fn new<I: I2c>(i2c: &mut I, chip_addr: u8) -> Result<Self, I::Error>
+    fn new<I: I2c>(i2c: &mut I, address: Address) -> Result<Self, Error<I::Error>>
     where
         Self: Sized,
     {
-        let mut read_buf = [0u8; 1];
-        i2c.write_read(chip_addr, &[Self::ADDRESS], &mut read_buf)?;
-        let read_buf = read_buf[0];
-        Ok(Self {
-            clear: (read_buf >> 7) != 0,
-            i_limit: ((read_buf >> 4) & 1) != 0,
-            ots: ((read_buf >> 3) & 1) != 0,
-            uvlo: ((read_buf >> 2) & 1) != 0,
-            ocp: ((read_buf >> 1) & 1) != 0,
-            fault: (read_buf & 1) != 0,
-        })
+        Ok(Self::decode(read_register(i2c, address, Self::ADDRESS)?))
     }
 }
 impl WriteRegister for Fault {
 
-    fn write<I: I2c>(&self, i2c: &mut I, chip_addr: u8) -> Result<(), I::Error> {
-        let write_buf = (u8::from(self.clear) << 7)
-            | (u8::from(self.i_limit) << 4)
-            | (u8::from(self.ots) << 3)
-            | (u8::from(self.uvlo) << 2)
-            | (u8::from(self.ocp) << 1)
-            | u8::from(self.fault);
-        i2c.write(chip_addr, &[Self::ADDRESS, write_buf])?;
+    fn write<I: I2c>(&self, i2c: &mut I, address: Address) -> Result<(), Error<I::Error>> {
+        i2c.write(address.as_u8(), &[Self::ADDRESS, self.encode()])
+            .map_err(Error::Bus)?;
         Ok(())
     }
 }
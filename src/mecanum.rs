@@ -0,0 +1,119 @@
+use embedded_hal::i2c::I2c;
+
+use crate::drive::{normalize, to_scale, Side};
+use crate::{Drv8830, Error};
+
+// Wheel positions and top speed of a mecanum (or X-configured omni) base
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MecanumGeometry {
+    // Distance between the front and rear axles
+    pub wheelbase_mm: u32,
+    // Distance between the left and right wheel contact points
+    pub track_width_mm: u32,
+    // Wheel surface speed at full output voltage
+    pub max_speed_mm_per_s: u32,
+}
+
+// Four motors in the order front-left, front-right, rear-left, rear-right, with rollers
+// forming an X seen from above
+#[derive(Debug)]
+pub struct MecanumDrive<I> {
+    wheels: [Drv8830<I>; 4],
+    geometry: MecanumGeometry,
+    sides: [Side; 4],
+}
+
+impl<I: I2c> MecanumDrive<I> {
+    pub fn new(
+        front_left: Drv8830<I>,
+        front_right: Drv8830<I>,
+        rear_left: Drv8830<I>,
+        rear_right: Drv8830<I>,
+        geometry: MecanumGeometry,
+    ) -> Self {
+        Self {
+            wheels: [front_left, front_right, rear_left, rear_right],
+            geometry,
+            sides: [Side::default(); 4],
+        }
+    }
+
+    // Flip wheels whose motors are mounted mirrored, in wheel order
+    pub fn with_inverted(mut self, inverted: [bool; 4]) -> Self {
+        for (side, inverted) in self.sides.iter_mut().zip(inverted) {
+            side.inverted = inverted;
+        }
+        self
+    }
+
+    pub fn geometry(&self) -> MecanumGeometry {
+        self.geometry
+    }
+
+    pub fn set_geometry(&mut self, geometry: MecanumGeometry) {
+        self.geometry = geometry;
+    }
+
+    // Body velocity: forward, leftward and counter-clockwise yaw rate. If any wheel
+    // would exceed full speed all four are scaled down together, so the base still
+    // moves in the requested direction, only slower.
+    pub fn set_velocity(
+        &mut self,
+        vx_mm_per_s: i32,
+        vy_mm_per_s: i32,
+        omega_mrad_per_s: i32,
+    ) -> Result<(), Error<I::Error>> {
+        let vx = i64::from(vx_mm_per_s);
+        let vy = i64::from(vy_mm_per_s);
+        // Wheel speed due to rotation: omega * (wheelbase + track) / 2
+        let lever_mm =
+            i64::from(self.geometry.wheelbase_mm) + i64::from(self.geometry.track_width_mm);
+        let turn = i64::from(omega_mrad_per_s) * lever_mm / 2000;
+        let max = self.geometry.max_speed_mm_per_s;
+        self.drive_mixed(normalize([
+            to_scale(vx - vy - turn, max),
+            to_scale(vx + vy + turn, max),
+            to_scale(vx + vy - turn, max),
+            to_scale(vx - vy + turn, max),
+        ]))
+    }
+
+    #[cfg(feature = "float")]
+    pub fn set_twist(
+        &mut self,
+        vx_m_per_s: f32,
+        vy_m_per_s: f32,
+        omega_rad_per_s: f32,
+    ) -> Result<(), Error<I::Error>> {
+        self.set_velocity(
+            (vx_m_per_s * 1000.0) as i32,
+            (vy_m_per_s * 1000.0) as i32,
+            (omega_rad_per_s * 1000.0) as i32,
+        )
+    }
+
+    pub fn stop(&mut self) -> Result<(), Error<I::Error>> {
+        let mut result = Ok(());
+        for wheel in &mut self.wheels {
+            result = result.and(wheel.stop());
+        }
+        result
+    }
+
+    pub fn wheels(&mut self) -> &mut [Drv8830<I>; 4] {
+        &mut self.wheels
+    }
+
+    pub fn release(self) -> [Drv8830<I>; 4] {
+        self.wheels
+    }
+
+    // Every wheel is written even if an earlier one fails; the first error is returned
+    fn drive_mixed(&mut self, speeds: [i32; 4]) -> Result<(), Error<I::Error>> {
+        let mut result = Ok(());
+        for ((wheel, side), speed) in self.wheels.iter_mut().zip(self.sides).zip(speeds) {
+            result = result.and(wheel.set_speed_q15(side.apply(speed)));
+        }
+        result
+    }
+}
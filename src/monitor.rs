@@ -0,0 +1,292 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{Drv8830, Error, Fault};
+
+// Individual fault conditions reported in the FAULT register
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FaultKind {
+    Ocp,
+    Ots,
+    Uvlo,
+    ILimit,
+}
+
+impl FaultKind {
+    pub const ALL: [Self; 4] = [Self::Ocp, Self::Ots, Self::Uvlo, Self::ILimit];
+
+    pub fn is_set(self, fault: &Fault) -> bool {
+        match self {
+            Self::Ocp => fault.ocp,
+            Self::Ots => fault.ots,
+            Self::Uvlo => fault.uvlo,
+            Self::ILimit => fault.i_limit,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+// What the monitor does while a fault is reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultPolicy {
+    // Write CLEAR once the fault has been reported continuously for `after_ms`
+    AutoClear { after_ms: u32 },
+    // Write CLEAR straight away, latching (and coasting) once the fault has come back
+    // `max_retries` times without staying away for at least `cooldown_ms`
+    ClearAndRetry { max_retries: u8, cooldown_ms: u32 },
+    // Coast the motor and leave the fault set until `FaultMonitor::reset`
+    LatchCoast,
+    // Brake the motor and leave the fault set until `FaultMonitor::reset`
+    LatchBrake,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaultPolicies {
+    pub ocp: FaultPolicy,
+    pub ots: FaultPolicy,
+    pub uvlo: FaultPolicy,
+    pub i_limit: FaultPolicy,
+}
+
+impl FaultPolicies {
+    pub fn get(&self, kind: FaultKind) -> FaultPolicy {
+        match kind {
+            FaultKind::Ocp => self.ocp,
+            FaultKind::Ots => self.ots,
+            FaultKind::Uvlo => self.uvlo,
+            FaultKind::ILimit => self.i_limit,
+        }
+    }
+}
+
+impl Default for FaultPolicies {
+    // Overcurrent and current limit retry a few times, temperature and supply faults
+    // recover on their own once the condition goes away
+    fn default() -> Self {
+        let retry = FaultPolicy::ClearAndRetry {
+            max_retries: 3,
+            cooldown_ms: 1000,
+        };
+        Self {
+            ocp: retry,
+            ots: FaultPolicy::AutoClear { after_ms: 1000 },
+            uvlo: FaultPolicy::AutoClear { after_ms: 100 },
+            i_limit: retry,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultEvent {
+    // The chip started reporting `kind`
+    Raised(FaultKind),
+    // Follows `Raised(FaultKind::ILimit)` when the driver knows its sense resistor:
+    // the motor sat at `limit_ma` for longer than 275 ms
+    CurrentLimited { limit_ma: u32 },
+    // The chip stopped reporting `kind`
+    Cleared(FaultKind),
+    // CLEAR was written because `kind` outlasted its `AutoClear` delay
+    AutoCleared(FaultKind),
+    // CLEAR was written under `ClearAndRetry`; `attempt` counts from 1
+    Retried { kind: FaultKind, attempt: u8 },
+    // The motor was stopped and `kind` is held until `FaultMonitor::reset`
+    Latched(FaultKind),
+}
+
+// Events produced by a single `FaultMonitor::poll`
+#[derive(Debug, Default, Clone)]
+pub struct FaultEvents {
+    events: [Option<FaultEvent>; 12],
+    len: usize,
+    next: usize,
+}
+
+impl FaultEvents {
+    fn push(&mut self, event: FaultEvent) {
+        // Each kind yields at most three events per poll, so this cannot overflow
+        self.events[self.len] = Some(event);
+        self.len += 1;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.next == self.len
+    }
+}
+
+impl Iterator for FaultEvents {
+    type Item = FaultEvent;
+
+    fn next(&mut self) -> Option<FaultEvent> {
+        let event = self.events.get(self.next).copied().flatten()?;
+        self.next += 1;
+        Some(event)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum State {
+    #[default]
+    Clear,
+    Active,
+    Latched,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Tracker {
+    state: State,
+    // When the current state was entered
+    since_us: u64,
+    retries: u8,
+}
+
+// Polls FAULT and applies a recovery policy per fault kind. Timestamps are in
+// microseconds from any monotonic clock.
+#[derive(Debug, Clone)]
+pub struct FaultMonitor {
+    policies: FaultPolicies,
+    trackers: [Tracker; 4],
+    last: Fault,
+}
+
+impl FaultMonitor {
+    pub fn new(policies: FaultPolicies) -> Self {
+        Self {
+            policies,
+            trackers: [Tracker::default(); 4],
+            last: Fault::default(),
+        }
+    }
+
+    pub fn policies(&self) -> &FaultPolicies {
+        &self.policies
+    }
+
+    pub fn set_policies(&mut self, policies: FaultPolicies) {
+        self.policies = policies;
+    }
+
+    // FAULT contents seen by the most recent poll
+    pub fn last_fault(&self) -> Fault {
+        self.last
+    }
+
+    pub fn is_latched(&self) -> bool {
+        self.trackers.iter().any(|t| t.state == State::Latched)
+    }
+
+    pub fn is_active(&self, kind: FaultKind) -> bool {
+        self.trackers[kind.index()].state != State::Clear
+    }
+
+    pub fn poll<I: I2c>(
+        &mut self,
+        driver: &mut Drv8830<I>,
+        now_us: u64,
+    ) -> Result<FaultEvents, Error<I::Error>> {
+        let fault = driver.read_fault()?;
+        self.last = fault;
+
+        let mut events = FaultEvents::default();
+        let mut clear = false;
+        let mut stop = None;
+        for kind in FaultKind::ALL {
+            let policy = self.policies.get(kind);
+            let tracker = &mut self.trackers[kind.index()];
+            let present = kind.is_set(&fault);
+            match (tracker.state, present) {
+                (State::Latched, _) => continue,
+                (State::Clear, false) => {
+                    if let FaultPolicy::ClearAndRetry { cooldown_ms, .. } = policy {
+                        if elapsed_ms(tracker.since_us, now_us) >= cooldown_ms {
+                            tracker.retries = 0;
+                        }
+                    }
+                    continue;
+                }
+                (State::Active, false) => {
+                    *tracker = Tracker {
+                        state: State::Clear,
+                        since_us: now_us,
+                        ..*tracker
+                    };
+                    events.push(FaultEvent::Cleared(kind));
+                    continue;
+                }
+                (State::Clear, true) => {
+                    tracker.state = State::Active;
+                    tracker.since_us = now_us;
+                    events.push(FaultEvent::Raised(kind));
+                    if let (FaultKind::ILimit, Some(limit)) = (kind, driver.current_limit()) {
+                        events.push(FaultEvent::CurrentLimited {
+                            limit_ma: limit.limit_ma(),
+                        });
+                    }
+                }
+                (State::Active, true) => {}
+            }
+
+            match policy {
+                FaultPolicy::AutoClear { after_ms } => {
+                    if elapsed_ms(tracker.since_us, now_us) >= after_ms {
+                        // A condition that is still there latches again straight away;
+                        // give it the full delay again rather than clearing every poll
+                        tracker.since_us = now_us;
+                        clear = true;
+                        events.push(FaultEvent::AutoCleared(kind));
+                    }
+                }
+                FaultPolicy::ClearAndRetry { max_retries, .. } if tracker.retries < max_retries => {
+                    tracker.retries += 1;
+                    clear = true;
+                    events.push(FaultEvent::Retried {
+                        kind,
+                        attempt: tracker.retries,
+                    });
+                }
+                FaultPolicy::ClearAndRetry { .. } | FaultPolicy::LatchCoast => {
+                    tracker.state = State::Latched;
+                    stop = stop.or(Some(FaultPolicy::LatchCoast));
+                    events.push(FaultEvent::Latched(kind));
+                }
+                FaultPolicy::LatchBrake => {
+                    tracker.state = State::Latched;
+                    stop = Some(FaultPolicy::LatchBrake);
+                    events.push(FaultEvent::Latched(kind));
+                }
+            }
+        }
+
+        match stop {
+            Some(FaultPolicy::LatchBrake) => driver.brake()?,
+            Some(_) => driver.coast()?,
+            None => {}
+        }
+        // Leave the fault bits visible while anything is latched
+        if clear && !self.is_latched() {
+            driver.clear_faults()?;
+        }
+        Ok(events)
+    }
+
+    // Release all latched faults and clear the FAULT register. The motor stays
+    // stopped until commanded again.
+    pub fn reset<I: I2c>(&mut self, driver: &mut Drv8830<I>) -> Result<(), Error<I::Error>> {
+        driver.clear_faults()?;
+        self.trackers = [Tracker::default(); 4];
+        self.last = Fault::default();
+        Ok(())
+    }
+}
+
+impl Default for FaultMonitor {
+    fn default() -> Self {
+        Self::new(FaultPolicies::default())
+    }
+}
+
+fn elapsed_ms(since_us: u64, now_us: u64) -> u32 {
+    let elapsed = now_us.saturating_sub(since_us) / 1000;
+    u32::try_from(elapsed).unwrap_or(u32::MAX)
+}
@@ -0,0 +1,91 @@
+// PID loop shared by the closed-loop controllers
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PidGains {
+    pub kp: f32,
+    pub ki: f32,
+    pub kd: f32,
+}
+
+impl PidGains {
+    pub fn new(kp: f32, ki: f32, kd: f32) -> Self {
+        Self { kp, ki, kd }
+    }
+}
+
+// The derivative acts on the measurement rather than the error, so setpoint steps do
+// not kick the output. The integral stops accumulating while the output is saturated
+// in the direction the error pushes it (conditional integration).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pid {
+    gains: PidGains,
+    min: f32,
+    max: f32,
+    // Already scaled by ki, so changing gains does not bump the output
+    integral: f32,
+    last_measurement: Option<f32>,
+}
+
+impl Pid {
+    pub fn new(gains: PidGains, min: f32, max: f32) -> Self {
+        let (min, max) = limits(min, max);
+        Self {
+            gains,
+            min,
+            max,
+            integral: 0.0,
+            last_measurement: None,
+        }
+    }
+
+    pub fn gains(&self) -> PidGains {
+        self.gains
+    }
+
+    pub fn set_gains(&mut self, gains: PidGains) {
+        self.gains = gains;
+    }
+
+    pub fn set_limits(&mut self, min: f32, max: f32) {
+        let (min, max) = limits(min, max);
+        self.min = min;
+        self.max = max;
+        self.integral = self.integral.clamp(min, max);
+    }
+
+    pub fn integral(&self) -> f32 {
+        self.integral
+    }
+
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.last_measurement = None;
+    }
+
+    // One step of `dt` seconds; `feed_forward` is added before clamping so the
+    // anti-windup sees the real output
+    pub fn update(&mut self, setpoint: f32, measurement: f32, feed_forward: f32, dt: f32) -> f32 {
+        let error = setpoint - measurement;
+        let p = self.gains.kp * error;
+        let d = match self.last_measurement {
+            Some(last) if dt > 0.0 => -self.gains.kd * (measurement - last) / dt,
+            _ => 0.0,
+        };
+        self.last_measurement = Some(measurement);
+
+        let integral = (self.integral + self.gains.ki * error * dt).clamp(self.min, self.max);
+        let output = feed_forward + p + integral + d;
+        let winding_up = (output > self.max && error > 0.0) || (output < self.min && error < 0.0);
+        if !winding_up {
+            self.integral = integral;
+        }
+        (feed_forward + p + self.integral + d).clamp(self.min, self.max)
+    }
+}
+
+// Limits given the wrong way round are swapped and a NaN limit becomes 0, so a bad
+// configuration limits the output instead of panicking in `clamp`
+fn limits(min: f32, max: f32) -> (f32, f32) {
+    let min = if min.is_nan() { 0.0 } else { min };
+    let max = if max.is_nan() { 0.0 } else { max };
+    (min.min(max), min.max(max))
+}
@@ -0,0 +1,297 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use crate::{Control, Direction, Drv8830, Error, Rounding, StopMode, VSet};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    // Constant slew rate
+    #[default]
+    Linear,
+    // Smoothstep: eases in and out, peaking at the configured slew rate mid-ramp
+    SCurve,
+}
+
+// What to do when a new target flips the direction of a running motor
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReversalPolicy {
+    // Switch straight over and ramp up from the minimum voltage
+    #[default]
+    Immediate,
+    // Ramp down to the minimum voltage, hold `stop` for `dwell_ms`, then ramp up
+    // in the new direction
+    Sequenced {
+        stop: StopMode,
+        dwell_ms: u32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RampConfig {
+    // Maximum rate of change of the output voltage
+    pub slew_mv_per_s: u32,
+    pub profile: Profile,
+    pub reversal: ReversalPolicy,
+}
+
+impl RampConfig {
+    pub fn new(slew_mv_per_s: u32, profile: Profile) -> Self {
+        Self {
+            slew_mv_per_s,
+            profile,
+            reversal: ReversalPolicy::Immediate,
+        }
+    }
+
+    pub fn with_reversal(mut self, reversal: ReversalPolicy) -> Self {
+        self.reversal = reversal;
+        self
+    }
+
+    #[cfg(feature = "float")]
+    pub fn volts_per_second(slew: f32, profile: Profile) -> Self {
+        Self::new((slew * 1000.0) as u32, profile)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    to: VSet,
+    from_mv: u16,
+    to_mv: u16,
+    start_us: u64,
+    duration_us: u64,
+}
+
+impl Segment {
+    fn millivolts_at(&self, now_us: u64, profile: Profile) -> u16 {
+        let elapsed = now_us.saturating_sub(self.start_us);
+        if elapsed >= self.duration_us {
+            return self.to_mv;
+        }
+        // Progress through the segment as a Q16 fraction
+        let x = (elapsed << 16) / self.duration_us;
+        let progress = match profile {
+            Profile::Linear => x,
+            Profile::SCurve => (((x * x) >> 16) * ((3 << 16) - 2 * x)) >> 16,
+        } as i64;
+        let delta = i64::from(self.to_mv) - i64::from(self.from_mv);
+        (i64::from(self.from_mv) + ((delta * progress) >> 16)) as u16
+    }
+}
+
+// Moves the VSET code of a driver toward a target at a limited slew rate, so the motor
+// is not slammed from standstill to full voltage. Stopping (coast or brake) is never
+// ramped; a change of direction is handled according to `RampConfig::reversal`.
+// Call `tick` regularly with a monotonic timestamp, or `run` to block until done.
+#[derive(Debug)]
+pub struct Ramp<I> {
+    driver: Drv8830<I>,
+    config: RampConfig,
+    // Last value written to the chip, only meaningful while `synced`
+    current: Control,
+    // False until `current` has been read from the driver, and again after the driver
+    // was handed out, since the motor may be running in either case
+    synced: bool,
+    target: Control,
+    // Started lazily by the first `tick` after a retarget
+    segment: Option<Segment>,
+    // End of the stop dwell of a sequenced reversal
+    dwell_until_us: Option<u64>,
+    // Timestamp of the latest `tick`, so `run` can continue on the same clock
+    now_us: u64,
+}
+
+impl<I: I2c> Ramp<I> {
+    pub fn new(driver: Drv8830<I>, config: RampConfig) -> Self {
+        Self {
+            driver,
+            config,
+            current: Control::COAST,
+            synced: false,
+            target: Control::COAST,
+            segment: None,
+            dwell_until_us: None,
+            now_us: 0,
+        }
+    }
+
+    pub fn config(&self) -> RampConfig {
+        self.config
+    }
+
+    // Takes effect from the next `tick`, continuing from the current output
+    pub fn set_config(&mut self, config: RampConfig) {
+        self.config = config;
+        self.segment = None;
+    }
+
+    pub fn target(&self) -> Control {
+        self.target
+    }
+
+    // Last value written to CONTROL, `None` until the first `tick` has read it
+    pub fn current(&self) -> Option<Control> {
+        self.synced.then_some(self.current)
+    }
+
+    // Retarget, mid-ramp or not; the new ramp starts from the current output
+    pub fn set_target(&mut self, target: Control) {
+        if target != self.target {
+            self.target = target;
+            self.segment = None;
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.synced && self.current == self.target
+    }
+
+    // Advance the ramp to `now_us`, returning true once the target has been reached
+    pub fn tick(&mut self, now_us: u64) -> Result<bool, Error<I::Error>> {
+        self.now_us = now_us;
+        if !self.synced {
+            self.sync()?;
+        }
+        if self.is_done() {
+            return Ok(true);
+        }
+        let direction = self.target.direction();
+        if !is_driving(direction) {
+            return self.write(self.target).map(|()| true);
+        }
+        if let Some(until_us) = self.dwell_until_us {
+            if now_us < until_us {
+                return Ok(false);
+            }
+            self.dwell_until_us = None;
+        }
+
+        let current = self.current.direction();
+        if current != direction {
+            match self.config.reversal {
+                ReversalPolicy::Sequenced { stop, dwell_ms } if is_driving(current) => {
+                    if self.current.vset != VSet::MIN {
+                        self.step_toward(VSet::MIN, now_us)?;
+                        return Ok(false);
+                    }
+                    self.write(stop.into())?;
+                    self.segment = None;
+                    self.dwell_until_us = Some(now_us + u64::from(dwell_ms) * 1000);
+                    return Ok(false);
+                }
+                _ => {
+                    self.write(Control::from_direction(direction, VSet::MIN))?;
+                    self.segment = None;
+                }
+            }
+        }
+        self.step_toward(self.target.vset, now_us)?;
+        Ok(self.is_done())
+    }
+
+    // Block until the target is reached, ticking every `period_us` (at least 1 µs, so
+    // the clock always advances)
+    pub fn run<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        period_us: u32,
+    ) -> Result<(), Error<I::Error>> {
+        let period_us = period_us.max(1);
+        let mut now_us = self.now_us;
+        while !self.tick(now_us)? {
+            delay.delay_us(period_us);
+            now_us += u64::from(period_us);
+        }
+        Ok(())
+    }
+
+    // Stop ramping and hold whatever was last written
+    pub fn hold(&mut self) -> Result<(), Error<I::Error>> {
+        if !self.synced {
+            self.sync()?;
+        }
+        self.target = self.current;
+        self.segment = None;
+        self.dwell_until_us = None;
+        Ok(())
+    }
+
+    // Direct access to the driver. Anything written through it is picked up by reading
+    // CONTROL back on the next `tick`.
+    pub fn driver(&mut self) -> &mut Drv8830<I> {
+        self.synced = false;
+        &mut self.driver
+    }
+
+    pub fn into_inner(self) -> Drv8830<I> {
+        self.driver
+    }
+
+    // Take the starting output from the chip (or the driver's cache), so a motor that
+    // is already running is still ramped down before a reversal
+    fn sync(&mut self) -> Result<(), Error<I::Error>> {
+        let readback = match self.driver.cached_control() {
+            Some(control) => control.into(),
+            None => self.driver.read_control()?,
+        };
+        // A reserved VSET code has no known voltage; assume the highest
+        self.current = readback
+            .control()
+            .unwrap_or(Control::from_direction(readback.direction(), VSet::MAX));
+        self.synced = true;
+        self.segment = None;
+        Ok(())
+    }
+
+    // Move the output along the segment ending at `to` in the current direction
+    fn step_toward(&mut self, to: VSet, now_us: u64) -> Result<(), Error<I::Error>> {
+        let segment = match self.segment {
+            Some(segment) if segment.to == to => segment,
+            _ => *self.segment.insert(self.start_segment(to, now_us)),
+        };
+        let millivolts = segment.millivolts_at(now_us, self.config.profile);
+        // Land exactly on the end code, whatever the rounding did along the way
+        let vset = if millivolts == segment.to_mv {
+            to
+        } else {
+            VSet::from_millivolts(millivolts, Rounding::Nearest)?
+        };
+        if vset != self.current.vset {
+            self.write(Control {
+                vset,
+                ..self.current
+            })?;
+        }
+        Ok(())
+    }
+
+    fn start_segment(&self, to: VSet, now_us: u64) -> Segment {
+        let from_mv = self.current.vset.to_millivolts();
+        let to_mv = to.to_millivolts();
+        let distance = u64::from(from_mv.abs_diff(to_mv));
+        let slew = u64::from(self.config.slew_mv_per_s.max(1));
+        let mut duration_us = distance * 1_000_000 / slew;
+        if self.config.profile == Profile::SCurve {
+            // Smoothstep peaks at 1.5x its average rate
+            duration_us = duration_us * 3 / 2;
+        }
+        Segment {
+            to,
+            from_mv,
+            to_mv,
+            start_us: now_us,
+            duration_us,
+        }
+    }
+
+    fn write(&mut self, control: Control) -> Result<(), Error<I::Error>> {
+        self.driver.write_control(control)?;
+        self.current = control;
+        Ok(())
+    }
+}
+
+fn is_driving(direction: Direction) -> bool {
+    matches!(direction, Direction::Forward | Direction::Reverse)
+}
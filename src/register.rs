@@ -0,0 +1,157 @@
+// Raw register values with typed accessors. Setters only touch their own bits, so
+// reserved bits survive a read-modify-write untouched.
+
+const fn bit(value: u8, n: u8) -> bool {
+    (value >> n) & 1 != 0
+}
+
+const fn with_bit(value: u8, n: u8, set: bool) -> u8 {
+    (value & !(1 << n)) | ((set as u8) << n)
+}
+
+// CONTROL (0x00): VSET[7:2], IN2[1], IN1[0]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ControlReg(u8);
+
+impl ControlReg {
+    pub const ADDRESS: u8 = 0x00;
+    const VSET_SHIFT: u8 = 2;
+    const IN2: u8 = 1;
+    const IN1: u8 = 0;
+
+    pub const fn new(raw: u8) -> Self {
+        Self(raw)
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    // Raw 6-bit code, including the reserved values 0x00-0x05
+    pub const fn vset_code(self) -> u8 {
+        self.0 >> Self::VSET_SHIFT
+    }
+
+    // Only the low six bits of `code` are used
+    pub fn set_vset_code(&mut self, code: u8) {
+        self.0 = (self.0 & 0b11) | (code << Self::VSET_SHIFT);
+    }
+
+    pub const fn in2(self) -> bool {
+        bit(self.0, Self::IN2)
+    }
+
+    pub fn set_in2(&mut self, set: bool) {
+        self.0 = with_bit(self.0, Self::IN2, set);
+    }
+
+    pub const fn in1(self) -> bool {
+        bit(self.0, Self::IN1)
+    }
+
+    pub fn set_in1(&mut self, set: bool) {
+        self.0 = with_bit(self.0, Self::IN1, set);
+    }
+}
+
+impl From<u8> for ControlReg {
+    fn from(raw: u8) -> Self {
+        Self(raw)
+    }
+}
+
+impl From<ControlReg> for u8 {
+    fn from(reg: ControlReg) -> Self {
+        reg.0
+    }
+}
+
+// FAULT (0x01): CLEAR[7], reserved[6:5], ILIMIT[4], OTS[3], UVLO[2], OCP[1], FAULT[0].
+// Only CLEAR is writable, the status bits are read-only.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FaultReg(u8);
+
+impl FaultReg {
+    pub const ADDRESS: u8 = 0x01;
+    pub const RESERVED_MASK: u8 = 0b0110_0000;
+    // The only value that needs writing: CLEAR set, everything else zero
+    pub const CLEAR_ONLY: Self = Self(1 << Self::CLEAR);
+    const CLEAR: u8 = 7;
+    const I_LIMIT: u8 = 4;
+    const OTS: u8 = 3;
+    const UVLO: u8 = 2;
+    const OCP: u8 = 1;
+    const FAULT: u8 = 0;
+
+    pub const fn new(raw: u8) -> Self {
+        Self(raw)
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub const fn clear(self) -> bool {
+        bit(self.0, Self::CLEAR)
+    }
+
+    pub fn set_clear(&mut self, set: bool) {
+        self.0 = with_bit(self.0, Self::CLEAR, set);
+    }
+
+    pub const fn i_limit(self) -> bool {
+        bit(self.0, Self::I_LIMIT)
+    }
+
+    pub fn set_i_limit(&mut self, set: bool) {
+        self.0 = with_bit(self.0, Self::I_LIMIT, set);
+    }
+
+    pub const fn ots(self) -> bool {
+        bit(self.0, Self::OTS)
+    }
+
+    pub fn set_ots(&mut self, set: bool) {
+        self.0 = with_bit(self.0, Self::OTS, set);
+    }
+
+    pub const fn uvlo(self) -> bool {
+        bit(self.0, Self::UVLO)
+    }
+
+    pub fn set_uvlo(&mut self, set: bool) {
+        self.0 = with_bit(self.0, Self::UVLO, set);
+    }
+
+    pub const fn ocp(self) -> bool {
+        bit(self.0, Self::OCP)
+    }
+
+    pub fn set_ocp(&mut self, set: bool) {
+        self.0 = with_bit(self.0, Self::OCP, set);
+    }
+
+    pub const fn fault(self) -> bool {
+        bit(self.0, Self::FAULT)
+    }
+
+    pub fn set_fault(&mut self, set: bool) {
+        self.0 = with_bit(self.0, Self::FAULT, set);
+    }
+
+    pub const fn reserved(self) -> u8 {
+        self.0 & Self::RESERVED_MASK
+    }
+}
+
+impl From<u8> for FaultReg {
+    fn from(raw: u8) -> Self {
+        Self(raw)
+    }
+}
+
+impl From<FaultReg> for u8 {
+    fn from(reg: FaultReg) -> Self {
+        reg.0
+    }
+}
@@ -0,0 +1,214 @@
+use embedded_hal::i2c::I2c;
+
+use crate::speed::volts_to_millivolts;
+use crate::{ControlError, Drv8830, Encoder, Pid, PidGains, VSet};
+
+// Position source for `PositionController`, in whatever units it counts
+pub trait PositionFeedback {
+    type Error;
+
+    fn position(&mut self) -> Result<i32, Self::Error>;
+}
+
+// Quadrature encoder counts as the position
+#[derive(Debug)]
+pub struct EncoderFeedback<E>(pub E);
+
+impl<E: Encoder> PositionFeedback for EncoderFeedback<E> {
+    type Error = E::Error;
+
+    fn position(&mut self) -> Result<i32, Self::Error> {
+        self.0.read().map(|reading| reading.count)
+    }
+}
+
+// Position from a closure, typically an ADC reading of a potentiometer
+#[derive(Debug)]
+pub struct AnalogFeedback<F>(pub F);
+
+impl<F, E> PositionFeedback for AnalogFeedback<F>
+where
+    F: FnMut() -> Result<i32, E>,
+{
+    type Error = E;
+
+    fn position(&mut self) -> Result<i32, Self::Error> {
+        (self.0)()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ServoConfig {
+    // Largest position error treated as on target; the motor brakes inside it
+    pub deadband: u32,
+    // Output voltage limit, at most 5.06 V. A NaN or infinite limit allows no output.
+    pub max_volts: f32,
+    // How long the error must stay inside the deadband to count as settled
+    pub settle_ms: u32,
+    // Give up and coast if not settled this long after a new target
+    pub timeout_ms: Option<u32>,
+}
+
+impl ServoConfig {
+    pub fn new(deadband: u32, max_volts: f32) -> Self {
+        Self {
+            deadband,
+            max_volts,
+            settle_ms: 0,
+            timeout_ms: None,
+        }
+    }
+
+    pub fn with_settle_time(mut self, settle_ms: u32) -> Self {
+        self.settle_ms = settle_ms;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout_ms: u32) -> Self {
+        self.timeout_ms = Some(timeout_ms);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServoStatus {
+    Moving,
+    Settled,
+    // Latched, with the motor coasting, until the next `set_target`
+    TimedOut,
+}
+
+// Drives a geared motor to a target position. Call `update` at a steady rate with a
+// monotonic timestamp; each call reads the feedback once and writes CONTROL once.
+#[derive(Debug)]
+pub struct PositionController<I, F> {
+    driver: Drv8830<I>,
+    feedback: F,
+    pid: Pid,
+    config: ServoConfig,
+    target: i32,
+    position: i32,
+    status: ServoStatus,
+    last_us: Option<u64>,
+    // Start of the timeout window, cleared once settled
+    started_us: Option<u64>,
+    in_band_since_us: Option<u64>,
+}
+
+impl<I: I2c, F: PositionFeedback> PositionController<I, F> {
+    pub fn new(driver: Drv8830<I>, feedback: F, gains: PidGains, config: ServoConfig) -> Self {
+        let max = max_volts(&config);
+        Self {
+            driver,
+            feedback,
+            pid: Pid::new(gains, -max, max),
+            config,
+            target: 0,
+            position: 0,
+            status: ServoStatus::Moving,
+            last_us: None,
+            started_us: None,
+            in_band_since_us: None,
+        }
+    }
+
+    pub fn config(&self) -> ServoConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: ServoConfig) {
+        let max = max_volts(&config);
+        self.pid.set_limits(-max, max);
+        self.config = config;
+    }
+
+    pub fn gains(&self) -> PidGains {
+        self.pid.gains()
+    }
+
+    pub fn set_gains(&mut self, gains: PidGains) {
+        self.pid.set_gains(gains);
+    }
+
+    pub fn target(&self) -> i32 {
+        self.target
+    }
+
+    // Starts a new move, restarting the timeout
+    pub fn set_target(&mut self, target: i32) {
+        self.target = target;
+        self.status = ServoStatus::Moving;
+        self.started_us = None;
+        self.in_band_since_us = None;
+    }
+
+    // Position read by the latest `update`
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    pub fn status(&self) -> ServoStatus {
+        self.status
+    }
+
+    pub fn update(&mut self, now_us: u64) -> Result<ServoStatus, ControlError<I::Error, F::Error>> {
+        self.position = self.feedback.position().map_err(ControlError::Feedback)?;
+        if self.status == ServoStatus::TimedOut {
+            return Ok(self.status);
+        }
+        let dt = self
+            .last_us
+            .replace(now_us)
+            .map_or(0.0, |last| now_us.saturating_sub(last) as f32 * 1e-6);
+        let started_us = *self.started_us.get_or_insert(now_us);
+
+        if self.target.abs_diff(self.position) <= self.config.deadband {
+            self.driver.brake()?;
+            self.pid.reset();
+            let since_us = *self.in_band_since_us.get_or_insert(now_us);
+            if now_us.saturating_sub(since_us) >= u64::from(self.config.settle_ms) * 1000 {
+                self.status = ServoStatus::Settled;
+                // A later disturbance gets a fresh timeout
+                self.started_us = None;
+            }
+            return Ok(self.status);
+        }
+
+        self.in_band_since_us = None;
+        self.status = ServoStatus::Moving;
+        let timed_out = self.config.timeout_ms.is_some_and(|timeout_ms| {
+            now_us.saturating_sub(started_us) >= u64::from(timeout_ms) * 1000
+        });
+        if timed_out {
+            self.driver.coast()?;
+            self.pid.reset();
+            self.status = ServoStatus::TimedOut;
+            return Ok(self.status);
+        }
+        let output = self
+            .pid
+            .update(self.target as f32, self.position as f32, 0.0, dt);
+        self.driver
+            .set_voltage_signed(volts_to_millivolts(output))?;
+        Ok(self.status)
+    }
+
+    pub fn driver(&mut self) -> &mut Drv8830<I> {
+        &mut self.driver
+    }
+
+    pub fn feedback(&mut self) -> &mut F {
+        &mut self.feedback
+    }
+
+    pub fn into_inner(self) -> (Drv8830<I>, F) {
+        (self.driver, self.feedback)
+    }
+}
+
+fn max_volts(config: &ServoConfig) -> f32 {
+    if !config.max_volts.is_finite() {
+        return 0.0;
+    }
+    config.max_volts.clamp(0.0, VSet::MAX.to_volts())
+}
@@ -0,0 +1,72 @@
+use crate::{Control, ControlReadback, ControlReg, Fault};
+
+// Last known CONTROL/FAULT contents, shared by the blocking and async drivers.
+// Only consulted while enabled; any bus error drops both copies. Only CONTROL writes
+// are ever skipped: FAULT latches in hardware, so its copy is informational.
+// The drivers only do the bus access and hand the result over here.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Shadow {
+    pub(crate) enabled: bool,
+    control: Option<u8>,
+    fault: Option<Fault>,
+}
+
+impl Shadow {
+    pub(crate) fn control(&self) -> Option<Control> {
+        self.control
+            .filter(|_| self.enabled)
+            .and_then(|raw| Control::decode(raw).ok())
+    }
+
+    pub(crate) fn fault(&self) -> Option<Fault> {
+        self.fault.filter(|_| self.enabled)
+    }
+
+    // A CONTROL write is redundant if the register already holds `control`
+    pub(crate) fn skip_control(&self, control: Control) -> bool {
+        self.enabled && self.control == Some(control.encode())
+    }
+
+    pub(crate) fn wrote_control<E>(
+        &mut self,
+        control: Control,
+        result: Result<(), E>,
+    ) -> Result<(), E> {
+        self.track(result)?;
+        self.control = Some(control.encode());
+        Ok(())
+    }
+
+    pub(crate) fn read_control<E>(&mut self, result: Result<u8, E>) -> Result<ControlReadback, E> {
+        let raw = self.track(result)?;
+        self.control = Some(raw);
+        Ok(ControlReg::from(raw).into())
+    }
+
+    pub(crate) fn read_fault<E>(&mut self, result: Result<u8, E>) -> Result<Fault, E> {
+        let fault = Fault::decode(self.track(result)?);
+        self.fault = Some(fault);
+        Ok(fault)
+    }
+
+    // After a CLEAR write: conditions that are still present will latch again, so the
+    // next read has to go to the chip to know
+    pub(crate) fn cleared<T, E>(&mut self, result: Result<T, E>) -> Result<T, E> {
+        let value = self.track(result)?;
+        self.fault = None;
+        Ok(value)
+    }
+
+    pub(crate) fn invalidate(&mut self) {
+        self.control = None;
+        self.fault = None;
+    }
+
+    // Drops the cache if `result` is an error, so the next access goes to the chip
+    fn track<T, E>(&mut self, result: Result<T, E>) -> Result<T, E> {
+        if result.is_err() {
+            self.invalidate();
+        }
+        result
+    }
+}
@@ -0,0 +1,292 @@
+// Register-level model of a DRV8830 for host-side testing, enabled with the `sim` feature.
+// It implements the blocking I2C trait, so the driver (or application code) can be
+// handed a `SimulatedDrv8830` in place of a real bus.
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+
+#[cfg(feature = "float")]
+use crate::VSet;
+use crate::{Address, ControlReg, FaultKind, FaultReg};
+
+#[cfg(feature = "float")]
+mod motor;
+
+#[cfg(feature = "float")]
+use motor::Drive;
+#[cfg(feature = "float")]
+pub use motor::{DcMotor, MotorParams};
+
+// How long the current may sit at the ISENSE limit before ILIMIT latches
+#[cfg(feature = "float")]
+const ILIMIT_DEGLITCH_S: f32 = 0.275;
+// Motor integration step; small enough for the electrical time constant of small motors
+#[cfg(feature = "float")]
+const MAX_SUBSTEP_S: f32 = 20e-6;
+
+#[derive(Debug, Clone)]
+pub struct SimulatedDrv8830 {
+    address: Address,
+    control: ControlReg,
+    fault: FaultReg,
+    // Faults that are still physically present and re-latch straight after a clear
+    conditions: FaultReg,
+    // Register selected by the last write, as on the real chip
+    pointer: u8,
+    nacks: u32,
+    lost_writes: u32,
+    transactions: u32,
+    register_writes: u32,
+    #[cfg(feature = "float")]
+    motor: Option<DcMotor>,
+    // ISENSE regulation limit in amps, `None` without a sense resistor
+    #[cfg(feature = "float")]
+    current_limit: Option<f32>,
+    #[cfg(feature = "float")]
+    ocp_threshold: f32,
+    #[cfg(feature = "float")]
+    limit_time: f32,
+}
+
+impl SimulatedDrv8830 {
+    pub fn new(address: Address) -> Self {
+        Self {
+            address,
+            control: ControlReg::default(),
+            fault: FaultReg::default(),
+            conditions: FaultReg::default(),
+            pointer: 0,
+            nacks: 0,
+            lost_writes: 0,
+            transactions: 0,
+            register_writes: 0,
+            #[cfg(feature = "float")]
+            motor: None,
+            #[cfg(feature = "float")]
+            current_limit: None,
+            #[cfg(feature = "float")]
+            ocp_threshold: 1.3,
+            #[cfg(feature = "float")]
+            limit_time: 0.0,
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub fn control(&self) -> ControlReg {
+        self.control
+    }
+
+    pub fn fault(&self) -> FaultReg {
+        self.fault
+    }
+
+    // False while a latched fault keeps the H-bridge disabled
+    pub fn bridge_enabled(&self) -> bool {
+        !self.fault.fault()
+    }
+
+    // Latch `kind` as if the chip had just detected it; a CLEAR write removes it
+    pub fn inject_fault(&mut self, kind: FaultKind) {
+        set_kind(&mut self.fault, kind, true);
+        self.fault.set_fault(true);
+    }
+
+    // Make `kind` persist (or stop persisting), so it latches again after every
+    // CLEAR until the condition is removed
+    pub fn set_condition(&mut self, kind: FaultKind, present: bool) {
+        set_kind(&mut self.conditions, kind, present);
+        if present {
+            self.inject_fault(kind);
+        }
+    }
+
+    // Fail the next `count` transactions with an address NACK
+    pub fn inject_nacks(&mut self, count: u32) {
+        self.nacks = count;
+    }
+
+    // Acknowledge but silently drop the next `count` register writes, like a
+    // corrupted write on a noisy bus
+    pub fn inject_lost_writes(&mut self, count: u32) {
+        self.lost_writes = count;
+    }
+
+    // Back to the power-on state; injected conditions are kept
+    pub fn power_cycle(&mut self) {
+        self.control = ControlReg::default();
+        self.fault = FaultReg::default();
+        self.pointer = 0;
+        self.latch_conditions();
+    }
+
+    // Transactions addressed to this chip, including NACKed ones
+    pub fn transactions(&self) -> u32 {
+        self.transactions
+    }
+
+    // Register writes that reached CONTROL or FAULT
+    pub fn register_writes(&self) -> u32 {
+        self.register_writes
+    }
+
+    // Connect a motor to the bridge output; it only moves when `step` is called
+    #[cfg(feature = "float")]
+    pub fn attach_motor(&mut self, motor: DcMotor) {
+        self.motor = Some(motor);
+    }
+
+    #[cfg(feature = "float")]
+    pub fn motor(&self) -> Option<&DcMotor> {
+        self.motor.as_ref()
+    }
+
+    #[cfg(feature = "float")]
+    pub fn motor_mut(&mut self) -> Option<&mut DcMotor> {
+        self.motor.as_mut()
+    }
+
+    // Current the chip regulates to through ISENSE, in amps; ILIMIT latches once the
+    // motor has been held there for 275 ms
+    #[cfg(feature = "float")]
+    pub fn set_current_limit(&mut self, amps: Option<f32>) {
+        self.current_limit = amps;
+    }
+
+    // Current in amps above which OCP latches immediately (1.3 A by default)
+    #[cfg(feature = "float")]
+    pub fn set_ocp_threshold(&mut self, amps: f32) {
+        self.ocp_threshold = amps;
+    }
+
+    // Advance the attached motor by `dt` seconds, latching OCP or ILIMIT as the real
+    // chip would. Does nothing without a motor.
+    #[cfg(feature = "float")]
+    pub fn step(&mut self, dt: f32) {
+        let Some(mut motor) = self.motor.take() else {
+            return;
+        };
+        let mut remaining = dt;
+        while remaining > 0.0 {
+            let h = remaining.min(MAX_SUBSTEP_S);
+            let current = motor.step(self.drive(), h, self.current_limit).abs();
+            if current > self.ocp_threshold {
+                self.inject_fault(FaultKind::Ocp);
+                self.limit_time = 0.0;
+            } else if self.current_limit.is_some_and(|limit| current >= limit) {
+                self.limit_time += h;
+                if self.limit_time >= ILIMIT_DEGLITCH_S {
+                    self.inject_fault(FaultKind::ILimit);
+                    self.limit_time = 0.0;
+                }
+            } else {
+                self.limit_time = 0.0;
+            }
+            remaining -= h;
+        }
+        self.motor = Some(motor);
+    }
+
+    // Any latched fault disables the bridge
+    #[cfg(feature = "float")]
+    fn drive(&self) -> Drive {
+        if !self.bridge_enabled() {
+            return Drive::Open;
+        }
+        // Reserved VSET codes are treated as no output
+        let volts = VSet::from_code(self.control.vset_code()).map_or(0.0, VSet::to_volts);
+        match (self.control.in1(), self.control.in2()) {
+            (false, false) => Drive::Open,
+            (true, false) => Drive::Voltage(volts),
+            (false, true) => Drive::Voltage(-volts),
+            (true, true) => Drive::Voltage(0.0),
+        }
+    }
+
+    fn latch_conditions(&mut self) {
+        let mut fault = self.conditions;
+        fault.set_fault(fault.bits() != 0);
+        self.fault = fault;
+    }
+
+    fn write_register(&mut self, value: u8) -> Result<(), ErrorKind> {
+        if self.lost_writes > 0 {
+            self.lost_writes -= 1;
+            return Ok(());
+        }
+        match self.pointer {
+            ControlReg::ADDRESS => self.control = ControlReg::new(value),
+            FaultReg::ADDRESS => {
+                // Status bits are read-only; CLEAR resets them and self-clears
+                if FaultReg::new(value).clear() {
+                    self.latch_conditions();
+                }
+            }
+            _ => return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data)),
+        }
+        self.register_writes += 1;
+        Ok(())
+    }
+
+    fn read_register(&self) -> Result<u8, ErrorKind> {
+        match self.pointer {
+            ControlReg::ADDRESS => Ok(self.control.bits()),
+            FaultReg::ADDRESS => Ok(self.fault.bits()),
+            _ => Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data)),
+        }
+    }
+}
+
+impl ErrorType for SimulatedDrv8830 {
+    type Error = ErrorKind;
+}
+
+impl I2c for SimulatedDrv8830 {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        if address != self.address.as_u8() {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        self.transactions += 1;
+        if self.nacks > 0 {
+            self.nacks -= 1;
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => {
+                    let Some((&register, data)) = bytes.split_first() else {
+                        continue;
+                    };
+                    if register > FaultReg::ADDRESS {
+                        return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data));
+                    }
+                    self.pointer = register;
+                    for &value in data {
+                        self.write_register(value)?;
+                        self.pointer += 1;
+                    }
+                }
+                Operation::Read(buffer) => {
+                    for byte in buffer.iter_mut() {
+                        *byte = self.read_register()?;
+                        self.pointer += 1;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn set_kind(reg: &mut FaultReg, kind: FaultKind, set: bool) {
+    match kind {
+        FaultKind::Ocp => reg.set_ocp(set),
+        FaultKind::Ots => reg.set_ots(set),
+        FaultKind::Uvlo => reg.set_uvlo(set),
+        FaultKind::ILimit => reg.set_i_limit(set),
+    }
+}
@@ -0,0 +1,138 @@
+// First-order brushed DC motor model, driven by the simulated bridge output.
+// SI units throughout: volts, amps, ohms, henries, radians and seconds.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotorParams {
+    pub resistance: f32,
+    // Zero makes the current follow the voltage instantly
+    pub inductance: f32,
+    // Torque constant, N*m/A
+    pub kt: f32,
+    // Back-EMF constant, V*s/rad
+    pub ke: f32,
+    // Rotor plus reflected load inertia, kg*m^2
+    pub inertia: f32,
+    // Viscous friction, N*m*s/rad
+    pub friction: f32,
+    // Constant load torque opposing motion, N*m
+    pub load_torque: f32,
+}
+
+impl Default for MotorParams {
+    // Roughly a small 3 V hobby gear motor seen from the motor shaft
+    fn default() -> Self {
+        Self {
+            resistance: 5.0,
+            inductance: 0.5e-3,
+            kt: 2.5e-3,
+            ke: 2.5e-3,
+            inertia: 1.0e-7,
+            friction: 1.0e-7,
+            load_torque: 0.0,
+        }
+    }
+}
+
+// What the bridge presents to the motor terminals
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Drive {
+    // High impedance: current decays through the body diodes
+    Open,
+    // Terminals at the given voltage; zero when braking
+    Voltage(f32),
+}
+
+#[derive(Debug, Clone)]
+pub struct DcMotor {
+    params: MotorParams,
+    current: f32,
+    speed: f32,
+    position: f32,
+}
+
+impl DcMotor {
+    pub fn new(params: MotorParams) -> Self {
+        Self {
+            params,
+            current: 0.0,
+            speed: 0.0,
+            position: 0.0,
+        }
+    }
+
+    pub fn params(&self) -> &MotorParams {
+        &self.params
+    }
+
+    pub fn params_mut(&mut self) -> &mut MotorParams {
+        &mut self.params
+    }
+
+    // Winding current in amps, positive when driven forward
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+
+    // Shaft speed in rad/s
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    // Accumulated shaft angle in radians
+    pub fn position(&self) -> f32 {
+        self.position
+    }
+
+    pub fn set_load_torque(&mut self, torque: f32) {
+        self.params.load_torque = torque;
+    }
+
+    // Advance by `dt` seconds. `current_limit` chops the current the way the chip's
+    // ISENSE regulation does. Returns the current before any chopping, for fault checks.
+    pub(crate) fn step(&mut self, drive: Drive, dt: f32, current_limit: Option<f32>) -> f32 {
+        let p = self.params;
+        let back_emf = p.ke * self.speed;
+        let raw_current = match drive {
+            // Reverse recovery through the diodes is fast compared to the mechanics
+            Drive::Open => 0.0,
+            Drive::Voltage(volts) if p.inductance <= 0.0 => (volts - back_emf) / p.resistance,
+            Drive::Voltage(volts) => {
+                let di = (volts - p.resistance * self.current - back_emf) / p.inductance * dt;
+                let next = self.current + di;
+                // Explicit Euler overshoots once dt approaches L/R; settle at steady state instead
+                let steady = (volts - back_emf) / p.resistance;
+                if (next - steady) * (self.current - steady) < 0.0 {
+                    steady
+                } else {
+                    next
+                }
+            }
+        };
+        self.current = match current_limit {
+            Some(limit) => raw_current.clamp(-limit, limit),
+            None => raw_current,
+        };
+
+        let drive_torque = p.kt * self.current - p.friction * self.speed;
+        let load = p.load_torque.abs();
+        let torque = if self.speed > 0.0 {
+            drive_torque - load
+        } else if self.speed < 0.0 {
+            drive_torque + load
+        } else if drive_torque.abs() <= load {
+            // Static: the load holds the shaft
+            0.0
+        } else {
+            drive_torque - load.copysign(drive_torque)
+        };
+        let next_speed = self.speed + torque / p.inertia * dt;
+        // Friction and load only ever bring the shaft to rest, never reverse it
+        self.speed = if next_speed * self.speed < 0.0 && drive_torque * self.speed <= 0.0 {
+            0.0
+        } else {
+            next_speed
+        };
+        self.position += self.speed * dt;
+        raw_current
+    }
+}
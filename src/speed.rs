@@ -0,0 +1,165 @@
+use core::f32::consts::TAU;
+
+use embedded_hal::i2c::I2c;
+
+use crate::{ControlError, Drv8830, Pid, PidGains, VSet};
+
+// Encoder count and the time it was sampled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncoderReading {
+    // Cumulative count; allowed to wrap
+    pub count: i32,
+    // Monotonic timestamp in microseconds
+    pub timestamp_us: u64,
+}
+
+pub trait Encoder {
+    type Error;
+
+    fn read(&mut self) -> Result<EncoderReading, Self::Error>;
+}
+
+// Open-loop voltage estimate for a target speed, added to the PID output
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FeedForward {
+    // Voltage needed to overcome friction, applied in the direction of the target
+    pub static_volts: f32,
+    pub volts_per_count_per_s: f32,
+}
+
+impl FeedForward {
+    // From the motor's back-EMF constant (V per rad/s) and the encoder resolution at
+    // the motor shaft
+    pub fn from_back_emf(ke: f32, counts_per_rev: u32) -> Self {
+        Self {
+            static_volts: 0.0,
+            volts_per_count_per_s: ke * TAU / counts_per_rev as f32,
+        }
+    }
+
+    pub fn with_static_volts(mut self, volts: f32) -> Self {
+        self.static_volts = volts;
+        self
+    }
+
+    fn volts(&self, target: f32) -> f32 {
+        if target == 0.0 {
+            return 0.0;
+        }
+        self.static_volts.copysign(target) + self.volts_per_count_per_s * target
+    }
+}
+
+// Holds a motor at a target speed in encoder counts per second. Call `update` at a
+// steady rate; each call reads the encoder once and writes CONTROL once.
+#[derive(Debug)]
+pub struct SpeedController<I, E> {
+    driver: Drv8830<I>,
+    encoder: E,
+    pid: Pid,
+    feed_forward: FeedForward,
+    target: f32,
+    last: Option<EncoderReading>,
+    speed: f32,
+    output_volts: f32,
+}
+
+impl<I: I2c, E: Encoder> SpeedController<I, E> {
+    pub fn new(driver: Drv8830<I>, encoder: E, gains: PidGains) -> Self {
+        let max = VSet::MAX.to_volts();
+        Self {
+            driver,
+            encoder,
+            pid: Pid::new(gains, -max, max),
+            feed_forward: FeedForward::default(),
+            target: 0.0,
+            last: None,
+            speed: 0.0,
+            output_volts: 0.0,
+        }
+    }
+
+    pub fn with_feed_forward(mut self, feed_forward: FeedForward) -> Self {
+        self.feed_forward = feed_forward;
+        self
+    }
+
+    pub fn set_feed_forward(&mut self, feed_forward: FeedForward) {
+        self.feed_forward = feed_forward;
+    }
+
+    pub fn gains(&self) -> PidGains {
+        self.pid.gains()
+    }
+
+    pub fn set_gains(&mut self, gains: PidGains) {
+        self.pid.set_gains(gains);
+    }
+
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    // Counts per second; zero stops the motor with the driver's stop mode
+    pub fn set_target(&mut self, counts_per_s: f32) {
+        self.target = counts_per_s;
+    }
+
+    // Speed measured by the latest `update`
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    // Voltage written by the latest `update`, before the driver's deadband
+    pub fn output_volts(&self) -> f32 {
+        self.output_volts
+    }
+
+    pub fn update(&mut self) -> Result<(), ControlError<I::Error, E::Error>> {
+        let reading = self.encoder.read().map_err(ControlError::Feedback)?;
+        // The first reading only gives the loop a starting point
+        let Some(last) = self.last.replace(reading) else {
+            return Ok(());
+        };
+        let dt_us = reading.timestamp_us.saturating_sub(last.timestamp_us);
+        if dt_us == 0 {
+            return Ok(());
+        }
+        let dt = dt_us as f32 * 1e-6;
+        self.speed = reading.count.wrapping_sub(last.count) as f32 / dt;
+
+        if self.target == 0.0 {
+            self.pid.reset();
+            self.output_volts = 0.0;
+            return Ok(self.driver.stop()?);
+        }
+        let feed_forward = self.feed_forward.volts(self.target);
+        self.output_volts = self.pid.update(self.target, self.speed, feed_forward, dt);
+        Ok(self
+            .driver
+            .set_voltage_signed(volts_to_millivolts(self.output_volts))?)
+    }
+
+    // Forget the loop state, e.g. after the motor was driven by something else
+    pub fn reset(&mut self) {
+        self.pid.reset();
+        self.last = None;
+    }
+
+    pub fn driver(&mut self) -> &mut Drv8830<I> {
+        &mut self.driver
+    }
+
+    pub fn encoder(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
+    pub fn into_inner(self) -> (Drv8830<I>, E) {
+        (self.driver, self.encoder)
+    }
+}
+
+// Rounds half away from zero; the PID output is already within the VSET range
+pub(crate) fn volts_to_millivolts(volts: f32) -> i16 {
+    (volts * 1000.0 + 0.5f32.copysign(volts)) as i16
+}
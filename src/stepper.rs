@@ -0,0 +1,351 @@
+use core::cmp::Ordering;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use crate::{Control, Direction, Drv8830, Error, VSet};
+
+// Coil A and coil B polarity for each half-step phase; wave drive uses the even phases
+// (one coil on) and full step the odd ones (both coils on)
+#[rustfmt::skip]
+const PHASES: [(i8, i8); 8] = [
+    (1, 0), (1, 1), (0, 1), (-1, 1),
+    (-1, 0), (-1, -1), (0, -1), (1, -1),
+];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StepMode {
+    // One coil at a time: least current, least torque
+    Wave,
+    // Both coils at a time: full torque
+    #[default]
+    FullStep,
+    // Alternates one and two coils: twice the resolution
+    HalfStep,
+}
+
+impl StepMode {
+    // Half-step phases advanced per step
+    fn stride(self) -> u8 {
+        match self {
+            StepMode::HalfStep => 1,
+            StepMode::Wave | StepMode::FullStep => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepDirection {
+    Forward,
+    Reverse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepperConfig {
+    pub mode: StepMode,
+    pub max_speed_steps_per_s: u32,
+    // 0 steps at full speed straight away
+    pub acceleration_steps_per_s2: u32,
+    // Coil voltage while stepping
+    pub run_vset: VSet,
+    // Coil voltage once a move finishes; `None` coasts both coils instead
+    pub hold_vset: Option<VSet>,
+}
+
+impl StepperConfig {
+    pub fn new(mode: StepMode, max_speed_steps_per_s: u32, run_vset: VSet) -> Self {
+        Self {
+            mode,
+            max_speed_steps_per_s,
+            acceleration_steps_per_s2: 0,
+            run_vset,
+            hold_vset: Some(run_vset),
+        }
+    }
+
+    pub fn with_acceleration(mut self, steps_per_s2: u32) -> Self {
+        self.acceleration_steps_per_s2 = steps_per_s2;
+        self
+    }
+
+    pub fn with_hold(mut self, hold_vset: Option<VSet>) -> Self {
+        self.hold_vset = hold_vset;
+        self
+    }
+}
+
+// Bipolar stepper with one DRV8830 per coil. Positions are counted in steps of the
+// configured mode. Call `tick` regularly with a monotonic timestamp, or `run` to block
+// until the target is reached.
+#[derive(Debug)]
+pub struct Stepper<I> {
+    coils: [Drv8830<I>; 2],
+    config: StepperConfig,
+    // Last value written to each coil, `None` when unknown: before the first write, after
+    // a failed one and after the coils were handed out
+    written: [Option<Control>; 2],
+    phase: u8,
+    position: i32,
+    target: i32,
+    // Current step rate in millisteps per second, 0 when stopped
+    speed: u64,
+    // Direction of the last step of the current move, 0 when stopped
+    moving: i32,
+    next_step_us: Option<u64>,
+    // Timestamp of the latest `tick`, so `run` can continue on the same clock
+    now_us: u64,
+}
+
+impl<I: I2c> Stepper<I> {
+    pub fn new(coil_a: Drv8830<I>, coil_b: Drv8830<I>, config: StepperConfig) -> Self {
+        let mut stepper = Self {
+            coils: [coil_a, coil_b],
+            config,
+            written: [None; 2],
+            phase: 0,
+            position: 0,
+            target: 0,
+            speed: 0,
+            moving: 0,
+            next_step_us: None,
+            now_us: 0,
+        };
+        stepper.align_phase();
+        stepper
+    }
+
+    pub fn config(&self) -> StepperConfig {
+        self.config
+    }
+
+    // A new mode takes effect from the next step, moving to the nearest phase it uses
+    pub fn set_config(&mut self, config: StepperConfig) {
+        self.config = config;
+        self.align_phase();
+    }
+
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    // Redefine the current position without moving, e.g. after homing
+    pub fn set_position(&mut self, position: i32) {
+        let remaining = i64::from(self.target) - i64::from(self.position);
+        self.target =
+            (i64::from(position) + remaining).clamp(i32::MIN.into(), i32::MAX.into()) as i32;
+        self.position = position;
+    }
+
+    pub fn target(&self) -> i32 {
+        self.target
+    }
+
+    // Retarget, moving or not; a reversal first decelerates the current move
+    pub fn move_to(&mut self, target: i32) {
+        self.target = target;
+    }
+
+    pub fn move_by(&mut self, steps: i32) {
+        self.target = self.target.saturating_add(steps);
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.position == self.target && self.speed == 0
+    }
+
+    pub fn speed_steps_per_s(&self) -> u32 {
+        (self.speed / 1000) as u32
+    }
+
+    // One step straight away at the run voltage, for step/direction style control.
+    // Cancels any move in progress.
+    pub fn step(&mut self, direction: StepDirection) -> Result<(), Error<I::Error>> {
+        let step = match direction {
+            StepDirection::Forward => 1,
+            StepDirection::Reverse => -1,
+        };
+        self.advance(step)?;
+        self.stop_motion();
+        Ok(())
+    }
+
+    // Step if one is due at `now_us`, returning true once the target has been reached
+    pub fn tick(&mut self, now_us: u64) -> Result<bool, Error<I::Error>> {
+        self.now_us = now_us;
+        if self.is_done() {
+            return Ok(true);
+        }
+        let due_us = *self.next_step_us.get_or_insert(now_us);
+        if now_us < due_us {
+            return Ok(false);
+        }
+        let wanted = direction(self.position, self.target);
+        // Keep going the same way while slowing down for a reversal
+        let step = if self.moving != 0 && wanted != self.moving && self.speed > self.min_speed() {
+            self.moving
+        } else {
+            wanted
+        };
+        if step == 0 {
+            self.finish()?;
+            return Ok(true);
+        }
+
+        self.advance(step)?;
+        self.moving = step;
+        if self.position == self.target {
+            self.finish()?;
+            return Ok(true);
+        }
+        let remaining = if direction(self.position, self.target) == step {
+            self.target.abs_diff(self.position)
+        } else {
+            0
+        };
+        self.speed = self.next_speed(remaining);
+        self.next_step_us = Some(due_us + 1_000_000_000 / self.speed);
+        Ok(false)
+    }
+
+    // Block until the target is reached, sleeping until each step is due
+    pub fn run<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<I::Error>> {
+        let mut now_us = self.now_us;
+        while !self.tick(now_us)? {
+            let wait_us = self
+                .next_step_us
+                .map_or(0, |due| due.saturating_sub(now_us));
+            delay.delay_us(u32::try_from(wait_us).unwrap_or(u32::MAX));
+            now_us += wait_us;
+        }
+        Ok(())
+    }
+
+    // Energise the current phase at the hold voltage (or the run voltage without one)
+    pub fn hold(&mut self) -> Result<(), Error<I::Error>> {
+        let vset = self.config.hold_vset.unwrap_or(self.config.run_vset);
+        self.energise(vset)
+    }
+
+    // Coast both coils so the shaft turns freely. Stops any move in progress.
+    pub fn release(&mut self) -> Result<(), Error<I::Error>> {
+        self.stop_motion();
+        self.write([Control::COAST; 2])
+    }
+
+    // Direct access to the drivers; the next write to each coil is never skipped
+    pub fn coils(&mut self) -> &mut [Drv8830<I>; 2] {
+        self.written = [None; 2];
+        &mut self.coils
+    }
+
+    pub fn into_inner(self) -> [Drv8830<I>; 2] {
+        self.coils
+    }
+
+    fn advance(&mut self, step: i32) -> Result<(), Error<I::Error>> {
+        let stride = self.config.mode.stride();
+        let phase = match step {
+            1 => (self.phase + stride) % 8,
+            _ => (self.phase + 8 - stride) % 8,
+        };
+        // Only count the step once both coils have taken it
+        self.write(phase_controls(phase, self.config.run_vset))?;
+        self.phase = phase;
+        self.position = self.position.saturating_add(step);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), Error<I::Error>> {
+        self.stop_motion();
+        match self.config.hold_vset {
+            Some(vset) => self.energise(vset),
+            None => self.write([Control::COAST; 2]),
+        }
+    }
+
+    fn stop_motion(&mut self) {
+        self.target = self.position;
+        self.speed = 0;
+        self.moving = 0;
+        self.next_step_us = None;
+    }
+
+    fn energise(&mut self, vset: VSet) -> Result<(), Error<I::Error>> {
+        self.write(phase_controls(self.phase, vset))
+    }
+
+    // Only coils whose command changed are written
+    fn write(&mut self, controls: [Control; 2]) -> Result<(), Error<I::Error>> {
+        for ((coil, written), control) in self.coils.iter_mut().zip(&mut self.written).zip(controls)
+        {
+            if *written != Some(control) {
+                *written = None;
+                coil.write_control(control)?;
+                *written = Some(control);
+            }
+        }
+        Ok(())
+    }
+
+    // Wave drive sits on the even phases and full step on the odd ones
+    fn align_phase(&mut self) {
+        match self.config.mode {
+            StepMode::Wave => self.phase &= !1,
+            StepMode::FullStep => self.phase |= 1,
+            StepMode::HalfStep => {}
+        }
+    }
+
+    fn max_speed(&self) -> u64 {
+        u64::from(self.config.max_speed_steps_per_s.max(1)) * 1000
+    }
+
+    // Rate reached one step after standstill, in millisteps per second
+    fn min_speed(&self) -> u64 {
+        match self.config.acceleration_steps_per_s2 {
+            0 => self.max_speed(),
+            accel => (2 * u64::from(accel) * 1_000_000)
+                .isqrt()
+                .min(self.max_speed()),
+        }
+    }
+
+    // Constant acceleration over one step: v'^2 = v^2 +- 2a, braking in time to stop
+    // on the target
+    fn next_speed(&self, remaining: u32) -> u64 {
+        let accel = u64::from(self.config.acceleration_steps_per_s2);
+        if accel == 0 {
+            return self.max_speed();
+        }
+        let delta = 2 * accel * 1_000_000;
+        let squared = self.speed.saturating_mul(self.speed);
+        let speed = if u64::from(remaining).saturating_mul(delta) <= squared {
+            squared.saturating_sub(delta).isqrt()
+        } else {
+            squared.saturating_add(delta).isqrt()
+        };
+        speed.clamp(self.min_speed(), self.max_speed())
+    }
+}
+
+// Sign of the step from `position` toward `target`
+fn direction(position: i32, target: i32) -> i32 {
+    match target.cmp(&position) {
+        Ordering::Greater => 1,
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+    }
+}
+
+fn phase_controls(phase: u8, vset: VSet) -> [Control; 2] {
+    let (a, b) = PHASES[usize::from(phase)];
+    [coil_control(a, vset), coil_control(b, vset)]
+}
+
+fn coil_control(polarity: i8, vset: VSet) -> Control {
+    match polarity {
+        1 => Control::from_direction(Direction::Forward, vset),
+        -1 => Control::from_direction(Direction::Reverse, vset),
+        _ => Control::COAST,
+    }
+}
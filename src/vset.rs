@@ -0,0 +1,130 @@
+use core::fmt;
+
+// Output voltage in millivolts for every valid VSET code, starting at 0x06.
+// Taken from the datasheet's voltage table, which the chip actually follows
+// (VOUT = 4 x 1.285 V x VSET / 64, rounded to 10 mV); codes 0x00-0x05 are reserved.
+#[rustfmt::skip]
+const MILLIVOLTS: [u16; 58] = [
+    480, 560, 640, 720, 800, 880, 960, 1040,
+    1120, 1200, 1290, 1370, 1450, 1530, 1610, 1690,
+    1770, 1850, 1930, 2010, 2090, 2170, 2250, 2330,
+    2410, 2490, 2570, 2650, 2730, 2810, 2890, 2970,
+    3050, 3130, 3210, 3290, 3370, 3450, 3530, 3610,
+    3690, 3770, 3860, 3940, 4020, 4100, 4180, 4260,
+    4340, 4420, 4500, 4580, 4660, 4740, 4820, 4900,
+    4980, 5060,
+];
+
+// How to pick a VSET code when the requested voltage falls between two table entries
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    #[default]
+    Nearest,
+    // Highest output voltage not above the request
+    Floor,
+    // Lowest output voltage not below the request
+    Ceil,
+}
+
+// A valid 6-bit VSET code (0x06 - 0x3F) for the CONTROL register
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VSet(u8);
+
+impl VSet {
+    pub const MIN: Self = Self(0x06);
+    pub const MAX: Self = Self(0x3F);
+    pub const MIN_MILLIVOLTS: u16 = MILLIVOLTS[0];
+    pub const MAX_MILLIVOLTS: u16 = MILLIVOLTS[MILLIVOLTS.len() - 1];
+
+    pub const fn from_code(code: u8) -> Result<Self, InvalidVoltage> {
+        if code < Self::MIN.0 || code > Self::MAX.0 {
+            return Err(InvalidVoltage);
+        }
+        Ok(Self(code))
+    }
+
+    // Voltages outside 0.48 V - 5.06 V are rejected regardless of rounding
+    pub fn from_millivolts(millivolts: u16, rounding: Rounding) -> Result<Self, InvalidVoltage> {
+        if !(Self::MIN_MILLIVOLTS..=Self::MAX_MILLIVOLTS).contains(&millivolts) {
+            return Err(InvalidVoltage);
+        }
+        let index = match MILLIVOLTS.binary_search(&millivolts) {
+            Ok(index) => index,
+            // `index` is the first entry above the request, never 0 or past the end here
+            Err(index) => match rounding {
+                Rounding::Floor => index - 1,
+                Rounding::Ceil => index,
+                Rounding::Nearest => {
+                    if millivolts - MILLIVOLTS[index - 1] <= MILLIVOLTS[index] - millivolts {
+                        index - 1
+                    } else {
+                        index
+                    }
+                }
+            },
+        };
+        Ok(Self(Self::MIN.0 + index as u8))
+    }
+
+    #[cfg(feature = "float")]
+    pub fn from_volts(volts: f32, rounding: Rounding) -> Result<Self, InvalidVoltage> {
+        // Also rejects NaN
+        if !(0.0..=f32::from(u16::MAX) / 1000.0).contains(&volts) {
+            return Err(InvalidVoltage);
+        }
+        let millivolts = volts * 1000.0;
+        let whole = millivolts as u16;
+        let millivolts = match rounding {
+            Rounding::Floor => whole,
+            Rounding::Ceil if millivolts > f32::from(whole) => whole.saturating_add(1),
+            Rounding::Ceil => whole,
+            Rounding::Nearest => (millivolts + 0.5) as u16,
+        };
+        Self::from_millivolts(millivolts, rounding)
+    }
+
+    pub const fn code(self) -> u8 {
+        self.0
+    }
+
+    pub const fn to_millivolts(self) -> u16 {
+        MILLIVOLTS[(self.0 - Self::MIN.0) as usize]
+    }
+
+    #[cfg(feature = "float")]
+    pub fn to_volts(self) -> f32 {
+        f32::from(self.to_millivolts()) / 1000.0
+    }
+}
+
+impl Default for VSet {
+    fn default() -> Self {
+        Self::MIN
+    }
+}
+
+impl TryFrom<u8> for VSet {
+    type Error = InvalidVoltage;
+
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        Self::from_code(code)
+    }
+}
+
+impl From<VSet> for u8 {
+    fn from(vset: VSet) -> Self {
+        vset.code()
+    }
+}
+
+// Returned for voltages (or VSET codes) the DRV8830 cannot output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidVoltage;
+
+impl fmt::Display for InvalidVoltage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("voltage outside the DRV8830 output range of 0.48 V - 5.06 V")
+    }
+}
+
+impl core::error::Error for InvalidVoltage {}
@@ -0,0 +1,48 @@
+use drv8830::{Address, AddressPin, InvalidAddress};
+
+use AddressPin::{High, Low, Open};
+
+// Datasheet table: (A1, A0) strapping and the resulting 7-bit address
+const TABLE: [(AddressPin, AddressPin, u8); 9] = [
+    (Low, Low, 0x60),
+    (Low, Open, 0x61),
+    (Low, High, 0x62),
+    (Open, Low, 0x63),
+    (Open, Open, 0x64),
+    (Open, High, 0x65),
+    (High, Low, 0x66),
+    (High, Open, 0x67),
+    (High, High, 0x68),
+];
+
+#[test]
+fn pins_map_to_datasheet_addresses() {
+    for (a1, a0, expected) in TABLE {
+        let address = Address::new(a1, a0);
+        assert_eq!(address.as_u8(), expected, "A1 {a1:?}, A0 {a0:?}");
+        assert_eq!(u8::from(address), expected);
+        assert_eq!((address.a1(), address.a0()), (a1, a0));
+    }
+    assert_eq!(Address::default().as_u8(), 0x60);
+}
+
+#[test]
+fn try_from_round_trips() {
+    for (a1, a0, value) in TABLE {
+        let address = Address::try_from(value).unwrap();
+        assert_eq!(address, Address::new(a1, a0));
+        assert_eq!(u8::from(address), value);
+    }
+}
+
+#[test]
+fn rejects_addresses_outside_the_range() {
+    for value in [0x00, 0x5F, 0x69, 0x7F, 0xFF] {
+        assert_eq!(Address::try_from(value), Err(InvalidAddress(value)));
+    }
+    assert_eq!(
+        InvalidAddress(0x5F).to_string(),
+        "0x5f is not a valid DRV8830 address"
+    );
+    assert_eq!(Address::new(High, Open).to_string(), "0x67");
+}
@@ -0,0 +1,93 @@
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+
+use drv8830::asynch::Drv8830;
+use drv8830::sim::SimulatedDrv8830;
+use drv8830::{Address, AddressPin, Control, Error, FaultKind, StopMode, VSet};
+use embedded_hal::i2c::{ErrorType, Operation};
+
+const ADDRESS: Address = Address::new(AddressPin::High, AddressPin::Low);
+
+// Async front for the simulator; every transaction completes on the first poll
+struct AsyncSim<'a>(&'a mut SimulatedDrv8830);
+
+impl ErrorType for AsyncSim<'_> {
+    type Error = <SimulatedDrv8830 as ErrorType>::Error;
+}
+
+impl embedded_hal_async::i2c::I2c for AsyncSim<'_> {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        embedded_hal::i2c::I2c::transaction(self.0, address, operations)
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn signed_voltage_uses_deadband_and_stop_mode() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    let mut driver = Drv8830::new(AsyncSim(&mut sim), ADDRESS).with_stop_mode(StopMode::Brake);
+    driver.set_deadband(1000);
+    block_on(driver.set_voltage_signed(-2000)).unwrap();
+    assert_eq!(
+        block_on(driver.read_control()).unwrap(),
+        Control::from_signed_millivolts(-2000, StopMode::Brake).unwrap()
+    );
+    block_on(driver.set_voltage_signed(999)).unwrap();
+    assert_eq!(block_on(driver.read_control()).unwrap(), Control::BRAKE);
+    assert!(matches!(
+        block_on(driver.set_voltage_signed(5100)),
+        Err(Error::InvalidVoltage)
+    ));
+}
+
+#[test]
+fn verify_retries_lost_writes() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    sim.inject_lost_writes(2);
+    let mut driver = Drv8830::new(AsyncSim(&mut sim), ADDRESS).with_verify(2);
+    block_on(driver.brake()).unwrap();
+    assert_eq!(sim.control(), Control::BRAKE.into());
+
+    sim.inject_lost_writes(3);
+    let mut driver = Drv8830::new(AsyncSim(&mut sim), ADDRESS).with_verify(2);
+    assert!(matches!(
+        block_on(driver.coast()),
+        Err(Error::VerifyMismatch { .. })
+    ));
+}
+
+#[test]
+fn cache_skips_redundant_writes() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    let mut driver = Drv8830::new(AsyncSim(&mut sim), ADDRESS).with_cache();
+    block_on(driver.forward(VSet::MAX)).unwrap();
+    block_on(driver.forward(VSet::MAX)).unwrap();
+    assert_eq!(driver.cached_control(), Some(Control::FORWARD));
+    block_on(driver.force_write(Control::FORWARD)).unwrap();
+    assert_eq!(sim.register_writes(), 2);
+}
+
+#[test]
+fn read_and_clear_keeps_snapshot() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    sim.inject_fault(FaultKind::Ocp);
+    let mut driver = Drv8830::new(AsyncSim(&mut sim), ADDRESS).with_cache();
+    let fault = block_on(driver.read_and_clear()).unwrap();
+    assert!(fault.fault && fault.ocp);
+    assert_eq!(driver.cached_fault(), None);
+    assert!(block_on(driver.check_fault()).is_ok());
+}
@@ -0,0 +1,62 @@
+use drv8830::CurrentLimit;
+
+fn rsense(target_ma: u32) -> Option<u32> {
+    CurrentLimit::for_target_ma(target_ma).map(CurrentLimit::rsense_milliohms)
+}
+
+#[test]
+fn picks_the_smallest_e24_resistor_not_above_the_target() {
+    // Exact E24 values
+    assert_eq!(rsense(1000), Some(200));
+    assert_eq!(rsense(2000), Some(100));
+    // Rounded up to the next resistor, so the limit rounds down
+    assert_eq!(rsense(900), Some(240));
+    assert_eq!(rsense(1500), Some(150));
+    assert_eq!(rsense(1400), Some(150));
+    assert_eq!(rsense(3), Some(68_000));
+    assert_eq!(CurrentLimit::for_target_ma(900).unwrap().limit_ma(), 833);
+    assert_eq!(rsense(0), None);
+}
+
+#[test]
+fn never_exceeds_the_target() {
+    for target_ma in (1..=20_000).step_by(7) {
+        let limit = CurrentLimit::for_target_ma(target_ma).unwrap();
+        assert!(limit.limit_ma() <= target_ma, "{target_ma} mA");
+        // Adjacent E24 values are at most about 15% apart
+        assert!(
+            limit.limit_ma() * 116 / 100 + 1 >= target_ma,
+            "{target_ma} mA"
+        );
+    }
+}
+
+#[test]
+fn resistance_is_at_least_10_milliohms() {
+    for target_ma in [20_000, 30_000, 100_000, u32::MAX] {
+        assert_eq!(rsense(target_ma), Some(10));
+    }
+    assert_eq!(
+        CurrentLimit::for_target_ma(u32::MAX).unwrap().limit_ma(),
+        20_000
+    );
+}
+
+#[test]
+fn exact_resistance_rounds_up() {
+    assert_eq!(CurrentLimit::exact_rsense_milliohms(0), None);
+    assert_eq!(CurrentLimit::exact_rsense_milliohms(1000), Some(200));
+    assert_eq!(CurrentLimit::exact_rsense_milliohms(900), Some(223));
+    assert_eq!(CurrentLimit::exact_rsense_milliohms(3), Some(66_667));
+}
+
+#[test]
+fn limit_and_power_from_resistance() {
+    assert_eq!(CurrentLimit::from_rsense_milliohms(0), None);
+    let limit = CurrentLimit::from_rsense_milliohms(330).unwrap();
+    assert_eq!(limit.limit_ma(), 606);
+    let limit = CurrentLimit::from_rsense_milliohms(200).unwrap();
+    assert_eq!(limit.limit_ma(), 1000);
+    assert_eq!(limit.max_power_mw(5000), 5000);
+    assert_eq!(limit.max_power_mw(480), 480);
+}
@@ -0,0 +1,123 @@
+use drv8830::sim::SimulatedDrv8830;
+use drv8830::{
+    Address, AddressPin, ControlReadback, DifferentialDrive, Direction, DriveGeometry, Drv8830,
+};
+
+const LEFT: Address = Address::new(AddressPin::Low, AddressPin::Low);
+const RIGHT: Address = Address::new(AddressPin::Low, AddressPin::High);
+
+const GEOMETRY: DriveGeometry = DriveGeometry {
+    track_width_mm: 200,
+    max_speed_mm_per_s: 1000,
+};
+
+// Output in signed millivolts, positive forward
+fn output(sim: &SimulatedDrv8830) -> i32 {
+    let readback = ControlReadback::from(sim.control());
+    let millivolts = readback
+        .vset()
+        .map_or(0, |vset| i32::from(vset.to_millivolts()));
+    match readback.direction() {
+        Direction::Forward => millivolts,
+        Direction::Reverse => -millivolts,
+        Direction::Coast | Direction::Brake => 0,
+    }
+}
+
+// What a single driver outputs for a Q15 speed
+fn expected(speed: i16) -> i32 {
+    let mut sim = SimulatedDrv8830::new(LEFT);
+    Drv8830::new(&mut sim, LEFT).set_speed_q15(speed).unwrap();
+    output(&sim)
+}
+
+fn run(
+    f: impl FnOnce(DifferentialDrive<&mut SimulatedDrv8830>) -> DifferentialDrive<&mut SimulatedDrv8830>,
+    command: impl FnOnce(&mut DifferentialDrive<&mut SimulatedDrv8830>),
+) -> (i32, i32) {
+    let mut left = SimulatedDrv8830::new(LEFT);
+    let mut right = SimulatedDrv8830::new(RIGHT);
+    let drive = DifferentialDrive::new(
+        Drv8830::new(&mut left, LEFT),
+        Drv8830::new(&mut right, RIGHT),
+        GEOMETRY,
+    );
+    command(&mut f(drive));
+    (output(&left), output(&right))
+}
+
+fn arcade(throttle: i16, turn: i16) -> (i32, i32) {
+    run(
+        |drive| drive,
+        |drive| drive.arcade_q15(throttle, turn).unwrap(),
+    )
+}
+
+fn velocity(linear_mm_per_s: i32, angular_mrad_per_s: i32) -> (i32, i32) {
+    run(
+        |drive| drive,
+        |drive| {
+            drive
+                .set_velocity(linear_mm_per_s, angular_mrad_per_s)
+                .unwrap()
+        },
+    )
+}
+
+#[test]
+fn arcade_sign_conventions() {
+    assert_eq!(arcade(16384, 0), (expected(16384), expected(16384)));
+    assert_eq!(arcade(-16384, 0), (expected(-16384), expected(-16384)));
+    // Positive turn is clockwise: left forward, right back
+    assert_eq!(arcade(0, 16384), (expected(16384), expected(-16384)));
+    assert_eq!(arcade(0, -16384), (expected(-16384), expected(16384)));
+    assert_eq!(arcade(0, 0), (0, 0));
+}
+
+#[test]
+fn velocity_sign_conventions() {
+    // Half of top speed straight ahead
+    assert_eq!(velocity(500, 0), (expected(16384), expected(16384)));
+    assert_eq!(velocity(-500, 0), (expected(-16384), expected(-16384)));
+    // Counter-clockwise at 1 rad/s: each side 100 mm/s, right forward
+    let (left, right) = velocity(0, 1000);
+    assert_eq!((left, right), (expected(-3276), expected(3276)));
+    assert!(left < 0 && right > 0);
+    let (left, right) = velocity(500, -1000);
+    assert!(left > right && right > 0);
+}
+
+#[test]
+fn normalization_keeps_ratios() {
+    // 1.5 : 0.5 scaled down to 1 : 1/3
+    let (left, right) = arcade(i16::MAX, i16::MAX / 2);
+    assert_eq!(left, expected(i16::MAX));
+    assert_eq!(right, expected(10923));
+    // Both sides over full scale keep driving straight
+    assert_eq!(velocity(3000, 0), (expected(i16::MAX), expected(i16::MAX)));
+    // Spinning faster than the wheels allow saturates both equally
+    let (left, right) = velocity(0, 100_000);
+    assert_eq!((left, right), (expected(i16::MIN + 1), expected(i16::MAX)));
+}
+
+#[test]
+fn inversion_and_trim() {
+    let (left, right) = run(
+        |drive| drive.with_inverted(false, true),
+        |drive| drive.arcade_q15(16384, 0).unwrap(),
+    );
+    assert_eq!((left, right), (expected(16384), expected(-16384)));
+
+    let (left, right) = run(
+        |drive| drive.with_trim(1000, 500),
+        |drive| drive.arcade_q15(16384, 0).unwrap(),
+    );
+    assert_eq!((left, right), (expected(16384), expected(8192)));
+
+    // Trim is applied after normalization, so a boost clips at full scale
+    let (left, right) = run(
+        |drive| drive.with_inverted(true, false).with_trim(1500, 1000),
+        |drive| drive.arcade_q15(24576, 0).unwrap(),
+    );
+    assert_eq!((left, right), (expected(-i16::MAX), expected(24576)));
+}
@@ -0,0 +1,76 @@
+use core::cell::RefCell;
+
+use drv8830::sim::SimulatedDrv8830;
+use drv8830::{Address, AddressPin, ControlReadback, Direction, Drv8830, Rounding, VSet};
+use embedded_hal_bus::i2c::RefCellDevice;
+
+const ADDRESS: Address = Address::new(AddressPin::Low, AddressPin::Low);
+
+fn output(sim: &RefCell<SimulatedDrv8830>) -> (Direction, Option<u8>) {
+    let readback = ControlReadback::from(sim.borrow().control());
+    (readback.direction(), readback.vset().map(VSet::code))
+}
+
+// Distance from `millivolts` to the voltage of `code` in the datasheet table
+fn error_mv(code: u8, millivolts: f64) -> f64 {
+    (f64::from(VSet::from_code(code).unwrap().to_millivolts()) - millivolts).abs()
+}
+
+#[test]
+fn set_speed_scales_to_full_output() {
+    let sim = RefCell::new(SimulatedDrv8830::new(ADDRESS));
+    let mut driver = Drv8830::new(RefCellDevice::new(&sim), ADDRESS);
+    for step in -1000..=1000 {
+        let speed = step as f32 / 1000.0;
+        driver.set_speed(speed).unwrap();
+        // Full scale is the chip's 5.06 V maximum
+        let millivolts = f64::from(speed) * 5060.0;
+        if millivolts.abs() < 480.0 {
+            assert_eq!(output(&sim).0, Direction::Coast, "speed {speed}");
+            continue;
+        }
+        let direction = if speed > 0.0 {
+            Direction::Forward
+        } else {
+            Direction::Reverse
+        };
+        let (actual, code) = output(&sim);
+        assert_eq!(actual, direction, "speed {speed}");
+        // The closest code, give or take a rounding tie
+        let best = (VSet::MIN.code()..=VSet::MAX.code())
+            .map(|code| error_mv(code, millivolts.abs()))
+            .fold(f64::INFINITY, f64::min);
+        assert!(
+            error_mv(code.unwrap(), millivolts.abs()) <= best + 1.0,
+            "speed {speed}: code {code:?}"
+        );
+    }
+
+    driver.set_speed(1.0).unwrap();
+    assert_eq!(output(&sim), (Direction::Forward, Some(0x3F)));
+    driver.set_speed(-1.0).unwrap();
+    assert_eq!(output(&sim), (Direction::Reverse, Some(0x3F)));
+    // 3.036 V, closest to the 3.05 V code
+    driver.set_speed(-0.6).unwrap();
+    assert_eq!(output(&sim), (Direction::Reverse, Some(0x26)));
+    // 0.455 V is below the minimum output
+    driver.set_speed(0.09).unwrap();
+    assert_eq!(output(&sim).0, Direction::Coast);
+    driver.set_speed(0.0).unwrap();
+    assert_eq!(output(&sim).0, Direction::Coast);
+    assert!(driver.set_speed(1.01).is_err());
+    assert!(driver.set_speed(f32::NAN).is_err());
+}
+
+#[test]
+fn from_volts_matches_from_millivolts() {
+    for rounding in [Rounding::Floor, Rounding::Ceil, Rounding::Nearest] {
+        for millivolts in 0..=6000u16 {
+            assert_eq!(
+                VSet::from_volts(f32::from(millivolts) / 1000.0, rounding),
+                VSet::from_millivolts(millivolts, rounding),
+                "{millivolts} mV, {rounding:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use drv8830::sim::SimulatedDrv8830;
+use drv8830::{Address, AddressPin, ControlReadback, Drv8830, MecanumDrive, MecanumGeometry};
+
+const ADDRESSES: [Address; 4] = [
+    Address::new(AddressPin::Low, AddressPin::Low),
+    Address::new(AddressPin::Low, AddressPin::High),
+    Address::new(AddressPin::High, AddressPin::Low),
+    Address::new(AddressPin::High, AddressPin::High),
+];
+
+const GEOMETRY: MecanumGeometry = MecanumGeometry {
+    wheelbase_mm: 200,
+    track_width_mm: 200,
+    max_speed_mm_per_s: 1000,
+};
+
+// What a single driver writes for a Q15 speed
+fn expected(speed: i16) -> ControlReadback {
+    let mut sim = SimulatedDrv8830::new(ADDRESSES[0]);
+    Drv8830::new(&mut sim, ADDRESSES[0])
+        .set_speed_q15(speed)
+        .unwrap();
+    sim.control().into()
+}
+
+fn expect(speeds: [i16; 4]) -> [ControlReadback; 4] {
+    speeds.map(expected)
+}
+
+// Wheel outputs (front-left, front-right, rear-left, rear-right) for a body velocity
+fn wheels(inverted: [bool; 4], vx: i32, vy: i32, omega: i32) -> [ControlReadback; 4] {
+    let mut sims = ADDRESSES.map(SimulatedDrv8830::new);
+    let [fl, fr, rl, rr] = &mut sims;
+    let mut drive = MecanumDrive::new(
+        Drv8830::new(fl, ADDRESSES[0]),
+        Drv8830::new(fr, ADDRESSES[1]),
+        Drv8830::new(rl, ADDRESSES[2]),
+        Drv8830::new(rr, ADDRESSES[3]),
+        GEOMETRY,
+    )
+    .with_inverted(inverted);
+    drive.set_velocity(vx, vy, omega).unwrap();
+    sims.each_ref().map(|sim| sim.control().into())
+}
+
+const UPRIGHT: [bool; 4] = [false; 4];
+// Half of full scale, for 500 mm/s at the wheel
+const HALF: i16 = 16384;
+
+#[test]
+fn pure_motions_turn_the_right_wheels() {
+    // Forward: all wheels forward
+    assert_eq!(wheels(UPRIGHT, 500, 0, 0), expect([HALF; 4]));
+    assert_eq!(wheels(UPRIGHT, -500, 0, 0), expect([-HALF; 4]));
+    // Leftward strafe: front-left and rear-right back, the other diagonal forward
+    assert_eq!(
+        wheels(UPRIGHT, 0, 500, 0),
+        expect([-HALF, HALF, HALF, -HALF])
+    );
+    assert_eq!(
+        wheels(UPRIGHT, 0, -500, 0),
+        expect([HALF, -HALF, -HALF, HALF])
+    );
+    // Counter-clockwise at 2.5 rad/s with a 200 mm lever arm: 500 mm/s per wheel,
+    // left side back and right side forward
+    assert_eq!(
+        wheels(UPRIGHT, 0, 0, 2500),
+        expect([-HALF, HALF, -HALF, HALF])
+    );
+    assert_eq!(
+        wheels(UPRIGHT, 0, 0, -2500),
+        expect([HALF, -HALF, HALF, -HALF])
+    );
+    assert_eq!(wheels(UPRIGHT, 0, 0, 0), expect([0; 4]));
+}
+
+#[test]
+fn motions_add_up_per_wheel() {
+    // Clockwise at 1 rad/s while going forward at 300 mm/s: 200 mm/s more on the
+    // left wheels and 200 mm/s less on the right
+    let (fast, slow) = (16384, 3276);
+    assert_eq!(
+        wheels(UPRIGHT, 300, 0, -1000),
+        expect([fast, slow, fast, slow])
+    );
+}
+
+#[test]
+fn diagonal_saturates_without_changing_direction() {
+    // Forward and left each at top speed: only the front-right/rear-left diagonal turns,
+    // scaled back to full output
+    assert_eq!(
+        wheels(UPRIGHT, 1000, 1000, 0),
+        expect([0, i16::MAX, i16::MAX, 0])
+    );
+}
+
+#[test]
+fn inverted_wheels_flip_sign() {
+    assert_eq!(
+        wheels([false, true, false, true], 500, 0, 0),
+        expect([HALF, -HALF, HALF, -HALF])
+    );
+}
@@ -0,0 +1,160 @@
+use drv8830::sim::SimulatedDrv8830;
+use drv8830::{
+    Address, AddressPin, Control, CurrentLimit, Drv8830, FaultEvent, FaultKind, FaultMonitor,
+    FaultPolicies, FaultPolicy, VSet,
+};
+
+const ADDRESS: Address = Address::new(AddressPin::Open, AddressPin::Open);
+
+fn poll(
+    monitor: &mut FaultMonitor,
+    driver: &mut Drv8830<&mut SimulatedDrv8830>,
+    now_ms: u64,
+) -> Vec<FaultEvent> {
+    monitor.poll(driver, now_ms * 1000).unwrap().collect()
+}
+
+#[test]
+fn auto_clear_waits_out_the_delay_each_time() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    sim.set_condition(FaultKind::Ots, true);
+    let mut monitor = FaultMonitor::new(FaultPolicies {
+        ots: FaultPolicy::AutoClear { after_ms: 100 },
+        ..FaultPolicies::default()
+    });
+    let mut driver = Drv8830::new(&mut sim, ADDRESS);
+    let mut cleared_at = Vec::new();
+    for now_ms in (0..=350).step_by(10) {
+        for event in poll(&mut monitor, &mut driver, now_ms) {
+            if event == FaultEvent::AutoCleared(FaultKind::Ots) {
+                cleared_at.push(now_ms);
+            }
+        }
+    }
+    // The condition re-latches after every CLEAR, which must not be written every poll
+    assert_eq!(cleared_at, [100, 200, 300]);
+    assert_eq!(sim.register_writes(), 3);
+
+    sim.set_condition(FaultKind::Ots, false);
+    let mut driver = Drv8830::new(&mut sim, ADDRESS);
+    assert_eq!(
+        poll(&mut monitor, &mut driver, 400),
+        [FaultEvent::AutoCleared(FaultKind::Ots)]
+    );
+    assert_eq!(
+        poll(&mut monitor, &mut driver, 410),
+        [FaultEvent::Cleared(FaultKind::Ots)]
+    );
+    assert!(!monitor.is_active(FaultKind::Ots));
+}
+
+#[test]
+fn latch_brake_holds_until_reset() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    let mut monitor = FaultMonitor::new(FaultPolicies {
+        ocp: FaultPolicy::LatchBrake,
+        ..FaultPolicies::default()
+    });
+    Drv8830::new(&mut sim, ADDRESS).forward(VSet::MAX).unwrap();
+    sim.inject_fault(FaultKind::Ocp);
+
+    let mut driver = Drv8830::new(&mut sim, ADDRESS);
+    assert_eq!(
+        poll(&mut monitor, &mut driver, 0),
+        [
+            FaultEvent::Raised(FaultKind::Ocp),
+            FaultEvent::Latched(FaultKind::Ocp)
+        ]
+    );
+    assert!(monitor.is_latched());
+    assert_eq!(driver.read_control().unwrap(), Control::BRAKE);
+    assert!(poll(&mut monitor, &mut driver, 1000).is_empty());
+    assert!(driver.read_fault().unwrap().ocp);
+
+    monitor.reset(&mut driver).unwrap();
+    assert!(!monitor.is_latched());
+    assert!(!driver.read_fault().unwrap().fault);
+}
+
+// A one-off overcurrent at `at_ms`, cleared by the monitor and gone by the next poll
+fn trip(sim: &mut SimulatedDrv8830, monitor: &mut FaultMonitor, at_ms: u64) -> Vec<FaultEvent> {
+    sim.inject_fault(FaultKind::Ocp);
+    let mut driver = Drv8830::new(sim, ADDRESS);
+    let events = poll(monitor, &mut driver, at_ms);
+    assert_eq!(
+        poll(monitor, &mut driver, at_ms + 10),
+        [FaultEvent::Cleared(FaultKind::Ocp)]
+    );
+    events
+}
+
+fn retried(attempt: u8) -> [FaultEvent; 2] {
+    [
+        FaultEvent::Raised(FaultKind::Ocp),
+        FaultEvent::Retried {
+            kind: FaultKind::Ocp,
+            attempt,
+        },
+    ]
+}
+
+#[test]
+fn retry_count_resets_after_cooldown() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    let mut monitor = FaultMonitor::new(FaultPolicies {
+        ocp: FaultPolicy::ClearAndRetry {
+            max_retries: 2,
+            cooldown_ms: 500,
+        },
+        ..FaultPolicies::default()
+    });
+    assert_eq!(trip(&mut sim, &mut monitor, 0), retried(1));
+    assert_eq!(trip(&mut sim, &mut monitor, 100), retried(2));
+    // Stays away for the cooldown, so the count starts over
+    let mut driver = Drv8830::new(&mut sim, ADDRESS);
+    assert!(poll(&mut monitor, &mut driver, 700).is_empty());
+    assert_eq!(trip(&mut sim, &mut monitor, 800), retried(1));
+    assert_eq!(trip(&mut sim, &mut monitor, 850), retried(2));
+
+    // Back again within the cooldown with no retries left
+    sim.inject_fault(FaultKind::Ocp);
+    let mut driver = Drv8830::new(&mut sim, ADDRESS);
+    assert_eq!(
+        poll(&mut monitor, &mut driver, 900),
+        [
+            FaultEvent::Raised(FaultKind::Ocp),
+            FaultEvent::Latched(FaultKind::Ocp)
+        ]
+    );
+    assert!(monitor.is_latched());
+}
+
+#[test]
+fn current_limit_event_reports_the_limit() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    sim.inject_fault(FaultKind::ILimit);
+    let limit = CurrentLimit::from_rsense_milliohms(200).unwrap();
+    let mut driver = Drv8830::new(&mut sim, ADDRESS).with_current_limit(limit);
+    let mut monitor = FaultMonitor::default();
+    assert_eq!(
+        poll(&mut monitor, &mut driver, 0),
+        [
+            FaultEvent::Raised(FaultKind::ILimit),
+            FaultEvent::CurrentLimited { limit_ma: 1000 },
+            FaultEvent::Retried {
+                kind: FaultKind::ILimit,
+                attempt: 1
+            }
+        ]
+    );
+
+    // Not reported for other faults, nor without a known sense resistor
+    sim.inject_fault(FaultKind::ILimit);
+    sim.inject_fault(FaultKind::Ocp);
+    let mut driver = Drv8830::new(&mut sim, ADDRESS);
+    let events = poll(&mut FaultMonitor::default(), &mut driver, 0);
+    assert!(!events
+        .iter()
+        .any(|event| matches!(event, FaultEvent::CurrentLimited { .. })));
+    assert!(events.contains(&FaultEvent::Raised(FaultKind::ILimit)));
+}
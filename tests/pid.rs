@@ -0,0 +1,77 @@
+use drv8830::{Pid, PidGains};
+
+const DT: f32 = 0.01;
+
+#[test]
+fn output_is_clamped() {
+    let mut pid = Pid::new(PidGains::new(10.0, 0.0, 0.0), -1.0, 1.0);
+    assert_eq!(pid.update(100.0, 0.0, 0.0, DT), 1.0);
+    assert_eq!(pid.update(-100.0, 0.0, 0.0, DT), -1.0);
+    assert_eq!(pid.update(0.05, 0.0, 0.0, DT), 0.5);
+    // Feed-forward counts towards the limits too
+    let mut pid = Pid::new(PidGains::default(), -2.0, 3.0);
+    assert_eq!(pid.update(0.0, 0.0, 5.0, DT), 3.0);
+    assert_eq!(pid.update(0.0, 0.0, -5.0, DT), -2.0);
+}
+
+#[test]
+fn integral_does_not_wind_up_while_saturated() {
+    let mut pid = Pid::new(PidGains::new(1.0, 10.0, 0.0), -1.0, 1.0);
+    for _ in 0..1000 {
+        assert_eq!(pid.update(10.0, 0.0, 0.0, DT), 1.0);
+    }
+    assert_eq!(pid.integral(), 0.0);
+    // So there is no stored overshoot to unwind once the target is passed
+    assert!(pid.update(10.0, 10.5, 0.0, DT) < 0.0);
+
+    // Saturated by feed-forward alone, the integral still holds
+    let mut pid = Pid::new(PidGains::new(0.0, 10.0, 0.0), -1.0, 1.0);
+    for _ in 0..100 {
+        pid.update(1.0, 0.0, 2.0, DT);
+    }
+    assert_eq!(pid.integral(), 0.0);
+}
+
+#[test]
+fn integral_unwinds_when_error_reverses() {
+    let mut pid = Pid::new(PidGains::new(0.0, 1.0, 0.0), -1.0, 1.0);
+    for _ in 0..50 {
+        pid.update(1.0, 0.0, 0.0, DT);
+    }
+    assert!((pid.integral() - 0.5).abs() < 1e-4);
+    // Saturated high but the error pushes down, so integration continues
+    for _ in 0..50 {
+        pid.update(-1.0, 0.0, 2.0, DT);
+    }
+    assert!(pid.integral().abs() < 1e-4);
+}
+
+#[test]
+fn integral_stays_within_limits() {
+    let mut pid = Pid::new(PidGains::new(0.0, 100.0, 0.0), -1.0, 1.0);
+    for _ in 0..100 {
+        pid.update(1.0, 0.0, 0.0, DT);
+    }
+    assert_eq!(pid.integral(), 1.0);
+    pid.set_limits(-0.5, 0.5);
+    assert_eq!(pid.integral(), 0.5);
+    assert_eq!(pid.update(0.0, 0.0, 0.0, DT), 0.5);
+}
+
+#[test]
+fn bad_limits_do_not_panic() {
+    let gains = PidGains::new(1.0, 1.0, 0.0);
+    // Swapped
+    let mut pid = Pid::new(gains, 1.0, -1.0);
+    assert_eq!(pid.update(10.0, 0.0, 0.0, DT), 1.0);
+    assert_eq!(pid.update(-10.0, 0.0, 0.0, DT), -1.0);
+    pid.set_limits(0.5, -0.5);
+    assert_eq!(pid.update(10.0, 0.0, 0.0, DT), 0.5);
+
+    // NaN limits become 0
+    let mut pid = Pid::new(gains, f32::NAN, f32::NAN);
+    assert_eq!(pid.update(10.0, 0.0, 0.0, DT), 0.0);
+    pid.set_limits(-1.0, f32::NAN);
+    assert_eq!(pid.update(10.0, 0.0, 0.0, DT), 0.0);
+    assert_eq!(pid.update(-10.0, 0.0, 0.0, DT), -1.0);
+}
@@ -0,0 +1,257 @@
+use core::cell::RefCell;
+
+use drv8830::sim::SimulatedDrv8830;
+use drv8830::{
+    Address, AddressPin, Control, ControlReadback, Direction, Drv8830, Profile, Ramp, RampConfig,
+    ReversalPolicy, Rounding, StopMode, VSet,
+};
+use embedded_hal::delay::DelayNs;
+use embedded_hal_bus::i2c::RefCellDevice;
+
+const ADDRESS: Address = Address::new(AddressPin::Low, AddressPin::Low);
+
+fn sequenced() -> RampConfig {
+    RampConfig::new(100_000, Profile::Linear).with_reversal(ReversalPolicy::Sequenced {
+        stop: StopMode::Brake,
+        dwell_ms: 20,
+    })
+}
+
+fn output(sim: &RefCell<SimulatedDrv8830>) -> Control {
+    ControlReadback::from(sim.borrow().control())
+        .control()
+        .unwrap()
+}
+
+#[test]
+fn sequenced_reversal_of_a_running_motor() {
+    let sim = RefCell::new(SimulatedDrv8830::new(ADDRESS));
+    // Already running when the ramp takes over
+    Drv8830::new(RefCellDevice::new(&sim), ADDRESS)
+        .forward(VSet::MAX)
+        .unwrap();
+
+    let mut ramp = Ramp::new(Drv8830::new(RefCellDevice::new(&sim), ADDRESS), sequenced());
+    ramp.set_target(Control::from_direction(Direction::Reverse, VSet::MAX));
+    let mut trace: Vec<(u64, Control)> = Vec::new();
+    let mut now_us = 0;
+    while !ramp.tick(now_us).unwrap() {
+        let control = output(&sim);
+        if trace.last().map(|&(_, last)| last) != Some(control) {
+            trace.push((now_us, control));
+        }
+        now_us += 1000;
+        assert!(now_us < 1_000_000, "ramp did not finish");
+    }
+    trace.push((now_us, output(&sim)));
+
+    let brake = trace
+        .iter()
+        .position(|&(_, control)| control == Control::BRAKE)
+        .unwrap();
+    let (down, rest) = trace.split_at(brake);
+    let reverse = &rest[1..];
+
+    // Ramp down in the original direction, never jumping straight to reverse
+    assert!(down.len() > 2);
+    assert!(down
+        .iter()
+        .all(|&(_, control)| control.direction() == Direction::Forward));
+    assert!(down
+        .windows(2)
+        .all(|pair| pair[1].1.vset.to_millivolts() < pair[0].1.vset.to_millivolts()));
+    assert_eq!(down.last().unwrap().1.vset, VSet::MIN);
+
+    // Brake for the dwell, then ramp up from the minimum in the new direction
+    assert!(reverse[0].0 - rest[0].0 >= 20_000);
+    assert_eq!(
+        reverse[0].1,
+        Control::from_direction(Direction::Reverse, VSet::MIN)
+    );
+    assert!(reverse
+        .iter()
+        .all(|&(_, control)| control.direction() == Direction::Reverse));
+    assert!(reverse
+        .windows(2)
+        .all(|pair| pair[1].1.vset.to_millivolts() > pair[0].1.vset.to_millivolts()));
+    assert_eq!(reverse.last().unwrap().1.vset, VSet::MAX);
+}
+
+#[test]
+fn writes_through_driver_are_picked_up() {
+    let sim = RefCell::new(SimulatedDrv8830::new(ADDRESS));
+    let mut ramp = Ramp::new(Drv8830::new(RefCellDevice::new(&sim), ADDRESS), sequenced());
+    assert!(ramp.tick(0).unwrap());
+    assert_eq!(ramp.current(), Some(Control::COAST));
+
+    ramp.driver().forward(VSet::MAX).unwrap();
+    assert_eq!(ramp.current(), None);
+    ramp.set_target(Control::from_direction(Direction::Reverse, VSet::MAX));
+    assert!(!ramp.tick(1000).unwrap());
+    let control = output(&sim);
+    assert_eq!(control.direction(), Direction::Forward);
+    assert_eq!(ramp.current(), Some(control));
+}
+
+const FULL: Control = Control::from_direction(Direction::Forward, VSet::MAX);
+
+struct Clock(u64);
+
+impl DelayNs for Clock {
+    fn delay_ns(&mut self, ns: u32) {
+        self.0 += u64::from(ns);
+    }
+}
+
+fn millivolts(sim: &RefCell<SimulatedDrv8830>) -> i32 {
+    i32::from(output(sim).vset.to_millivolts())
+}
+
+fn forward(millivolts: u16) -> Control {
+    Control::from_direction(
+        Direction::Forward,
+        VSet::from_millivolts(millivolts, Rounding::Nearest).unwrap(),
+    )
+}
+
+// Ticks every millisecond until done, checking the output against `expected_mv` on
+// the way and returning the time taken
+fn follow(
+    ramp: &mut Ramp<RefCellDevice<'_, SimulatedDrv8830>>,
+    sim: &RefCell<SimulatedDrv8830>,
+    start_us: u64,
+    expected_mv: impl Fn(u64) -> i32,
+) -> u64 {
+    let mut now_us = start_us;
+    while !ramp.tick(now_us).unwrap() {
+        let expected = expected_mv(now_us - start_us);
+        // Within one VSET step of the ideal curve
+        assert!(
+            (millivolts(sim) - expected).abs() <= 50,
+            "{} mV at {now_us} µs, expected {expected} mV",
+            millivolts(sim)
+        );
+        now_us += 1000;
+        assert!(now_us < start_us + 60_000_000, "ramp did not finish");
+    }
+    now_us - start_us
+}
+
+#[test]
+fn linear_ramp_keeps_the_slew_rate() {
+    let sim = RefCell::new(SimulatedDrv8830::new(ADDRESS));
+    let config = RampConfig::new(1000, Profile::Linear);
+    let mut ramp = Ramp::new(Drv8830::new(RefCellDevice::new(&sim), ADDRESS), config);
+    ramp.set_target(FULL);
+
+    // Starts from the minimum voltage, then 1 mV per ms up to 5060 mV
+    let elapsed_us = follow(&mut ramp, &sim, 0, |t_us| 480 + (t_us / 1000) as i32);
+    assert_eq!(output(&sim), FULL);
+    // Rounding lands on the last code up to half a step (40 mV) early
+    assert!(
+        (4_530_000..=4_580_000).contains(&elapsed_us),
+        "{elapsed_us}"
+    );
+}
+
+#[test]
+fn s_curve_eases_in_and_out() {
+    let sim = RefCell::new(SimulatedDrv8830::new(ADDRESS));
+    let config = RampConfig::new(1000, Profile::SCurve);
+    let mut ramp = Ramp::new(Drv8830::new(RefCellDevice::new(&sim), ADDRESS), config);
+    ramp.set_target(FULL);
+
+    // Smoothstep over 1.5x the linear duration
+    let duration = 4_580_000.0 * 1.5;
+    let smoothstep = |t_us: u64| {
+        let x = t_us as f64 / duration;
+        480 + (4580.0 * x * x * (3.0 - 2.0 * x)) as i32
+    };
+    let elapsed_us = follow(&mut ramp, &sim, 0, smoothstep);
+    assert_eq!(output(&sim), FULL);
+    // Well beyond the 4.58 s a linear ramp takes, even landing half a step early
+    // where the curve flattens out
+    assert!(
+        (6_000_000..=6_870_000).contains(&elapsed_us),
+        "{elapsed_us}"
+    );
+
+    // Slow at both ends, a quarter of the way through it is well behind linear
+    assert_eq!(smoothstep(6_870_000 / 4), 480 + 715);
+    assert_eq!(smoothstep(6_870_000 / 2), 480 + 2290);
+}
+
+#[test]
+fn retarget_mid_ramp_continues_from_the_current_output() {
+    let sim = RefCell::new(SimulatedDrv8830::new(ADDRESS));
+    let config = RampConfig::new(1000, Profile::Linear);
+    let mut ramp = Ramp::new(Drv8830::new(RefCellDevice::new(&sim), ADDRESS), config);
+    ramp.set_target(FULL);
+    let mut now_us = 0;
+    while now_us <= 2_000_000 {
+        assert!(!ramp.tick(now_us).unwrap());
+        now_us += 1000;
+    }
+    let reached = millivolts(&sim);
+    assert!((reached - 2480).abs() <= 50, "{reached}");
+
+    // Back down to 1 V from wherever the first ramp got to, at the same rate
+    let target = forward(1000);
+    ramp.set_target(target);
+    let end = i32::from(target.vset.to_millivolts());
+    let elapsed_us = follow(&mut ramp, &sim, now_us, |t_us| {
+        reached - (t_us / 1000) as i32
+    });
+    assert_eq!(output(&sim), target);
+    let expected_us = (reached - end) as u64 * 1000;
+    assert!(
+        (expected_us - 50_000..=expected_us).contains(&elapsed_us),
+        "{elapsed_us}"
+    );
+}
+
+#[test]
+fn hold_stops_where_the_ramp_is() {
+    let sim = RefCell::new(SimulatedDrv8830::new(ADDRESS));
+    let config = RampConfig::new(1000, Profile::Linear);
+    let mut ramp = Ramp::new(Drv8830::new(RefCellDevice::new(&sim), ADDRESS), config);
+    ramp.set_target(FULL);
+    for now_us in (0..=1_000_000).step_by(1000) {
+        assert!(!ramp.tick(now_us).unwrap());
+    }
+    let held = output(&sim);
+    ramp.hold().unwrap();
+    assert_eq!(ramp.target(), held);
+    assert!(ramp.is_done());
+    let writes = sim.borrow().register_writes();
+    assert!(ramp.tick(5_000_000).unwrap());
+    assert_eq!(output(&sim), held);
+    assert_eq!(sim.borrow().register_writes(), writes);
+
+    // Before any tick, holds whatever the chip is already doing
+    let mut ramp = Ramp::new(Drv8830::new(RefCellDevice::new(&sim), ADDRESS), config);
+    ramp.hold().unwrap();
+    assert_eq!(ramp.target(), held);
+}
+
+#[test]
+fn run_advances_the_clock() {
+    let sim = RefCell::new(SimulatedDrv8830::new(ADDRESS));
+    let config = RampConfig::new(1_000_000, Profile::Linear);
+    let mut ramp = Ramp::new(Drv8830::new(RefCellDevice::new(&sim), ADDRESS), config);
+    ramp.set_target(FULL);
+    let mut clock = Clock(0);
+    ramp.run(&mut clock, 100).unwrap();
+    assert_eq!(output(&sim), FULL);
+    assert!((4_500_000..=4_700_000).contains(&clock.0), "{}", clock.0);
+
+    // A zero period still finishes, ticking every microsecond
+    ramp.set_target(Control::from_direction(Direction::Reverse, VSet::MAX));
+    let mut clock = Clock(0);
+    ramp.run(&mut clock, 0).unwrap();
+    assert_eq!(
+        output(&sim),
+        Control::from_direction(Direction::Reverse, VSet::MAX)
+    );
+    assert!((4_500_000..=4_700_000).contains(&clock.0), "{}", clock.0);
+}
@@ -0,0 +1,104 @@
+use drv8830::{Control, ControlReg, Direction, Fault, FaultReg, VSet};
+
+#[test]
+fn control_reg_round_trips_all_values() {
+    for raw in 0..=u8::MAX {
+        let reg = ControlReg::from(raw);
+        assert_eq!(u8::from(reg), raw);
+
+        let mut rebuilt = ControlReg::default();
+        rebuilt.set_vset_code(reg.vset_code());
+        rebuilt.set_in2(reg.in2());
+        rebuilt.set_in1(reg.in1());
+        assert_eq!(rebuilt, reg, "raw {raw:#04x}");
+    }
+}
+
+#[test]
+fn control_reg_setters_only_touch_their_bits() {
+    for raw in 0..=u8::MAX {
+        let mut reg = ControlReg::from(raw);
+        reg.set_in1(!reg.in1());
+        assert_eq!(u8::from(reg), raw ^ 0b01);
+
+        let mut reg = ControlReg::from(raw);
+        reg.set_in2(!reg.in2());
+        assert_eq!(u8::from(reg), raw ^ 0b10);
+
+        let mut reg = ControlReg::from(raw);
+        reg.set_vset_code(!reg.vset_code());
+        assert_eq!(u8::from(reg), raw ^ 0b1111_1100);
+    }
+}
+
+#[test]
+fn control_decodes_every_valid_value() {
+    for raw in 0..=u8::MAX {
+        let reg = ControlReg::from(raw);
+        match Control::try_from(reg) {
+            Ok(control) => assert_eq!(ControlReg::from(control), reg, "raw {raw:#04x}"),
+            // Only the reserved VSET codes may fail
+            Err(_) => assert!(reg.vset_code() < VSet::MIN.code(), "raw {raw:#04x}"),
+        }
+    }
+}
+
+#[test]
+fn control_encodes_every_command() {
+    let directions = [
+        Direction::Coast,
+        Direction::Reverse,
+        Direction::Forward,
+        Direction::Brake,
+    ];
+    for direction in directions {
+        for code in VSet::MIN.code()..=VSet::MAX.code() {
+            let control = Control::from_direction(direction, VSet::from_code(code).unwrap());
+            assert_eq!(Control::try_from(ControlReg::from(control)), Ok(control));
+        }
+    }
+}
+
+#[test]
+fn fault_reg_round_trips_all_values() {
+    for raw in 0..=u8::MAX {
+        let reg = FaultReg::from(raw);
+        assert_eq!(u8::from(reg), raw);
+
+        let mut rebuilt = FaultReg::from(reg.reserved());
+        rebuilt.set_clear(reg.clear());
+        rebuilt.set_i_limit(reg.i_limit());
+        rebuilt.set_ots(reg.ots());
+        rebuilt.set_uvlo(reg.uvlo());
+        rebuilt.set_ocp(reg.ocp());
+        rebuilt.set_fault(reg.fault());
+        assert_eq!(rebuilt, reg, "raw {raw:#04x}");
+    }
+}
+
+#[test]
+fn fault_reg_setters_preserve_reserved_bits() {
+    for raw in 0..=u8::MAX {
+        let mut reg = FaultReg::from(raw);
+        reg.set_clear(!reg.clear());
+        reg.set_i_limit(!reg.i_limit());
+        reg.set_ots(!reg.ots());
+        reg.set_uvlo(!reg.uvlo());
+        reg.set_ocp(!reg.ocp());
+        reg.set_fault(!reg.fault());
+        assert_eq!(u8::from(reg), raw ^ !FaultReg::RESERVED_MASK);
+    }
+}
+
+#[test]
+fn fault_decodes_every_value() {
+    for raw in 0..=u8::MAX {
+        let fault = Fault::from(FaultReg::from(raw));
+        assert_eq!(
+            u8::from(FaultReg::from(fault)),
+            raw & !FaultReg::RESERVED_MASK,
+            "raw {raw:#04x}"
+        );
+        assert_eq!(Fault::from(FaultReg::from(fault)), fault);
+    }
+}
@@ -0,0 +1,124 @@
+use core::cell::Cell;
+use core::convert::Infallible;
+
+use drv8830::sim::SimulatedDrv8830;
+use drv8830::{
+    Address, AddressPin, AnalogFeedback, Control, Direction, Drv8830, PidGains, PositionController,
+    ServoConfig, ServoStatus,
+};
+
+const ADDRESS: Address = Address::new(AddressPin::High, AddressPin::Open);
+
+// Proportional only, 1 V per count, so any error outside the deadband drives hard
+fn gains() -> PidGains {
+    PidGains::new(1.0, 0.0, 0.0)
+}
+
+#[test]
+fn deadband_brakes_and_outside_drives_toward_target() {
+    let position = Cell::new(90);
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    let mut servo = PositionController::new(
+        Drv8830::new(&mut sim, ADDRESS),
+        AnalogFeedback(|| Ok::<_, Infallible>(position.get())),
+        gains(),
+        ServoConfig::new(5, 3.0),
+    );
+    servo.set_target(100);
+    assert_eq!(servo.update(0).unwrap(), ServoStatus::Moving);
+    let control = servo.driver().read_control().unwrap();
+    assert_eq!(control.direction(), Direction::Forward);
+    // Limited to `max_volts`
+    assert!(control.vset().unwrap().to_millivolts() <= 3000);
+
+    position.set(110);
+    assert_eq!(servo.update(1000).unwrap(), ServoStatus::Moving);
+    assert_eq!(
+        servo.driver().read_control().unwrap().direction(),
+        Direction::Reverse
+    );
+
+    // Within 5 counts either side counts as there
+    for at in [95, 105] {
+        position.set(at);
+        assert_eq!(servo.update(2000).unwrap(), ServoStatus::Settled);
+        assert_eq!(servo.driver().read_control().unwrap(), Control::BRAKE);
+    }
+    assert_eq!(servo.position(), 105);
+}
+
+#[test]
+fn settles_only_after_staying_in_band() {
+    let position = Cell::new(100);
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    let mut servo = PositionController::new(
+        Drv8830::new(&mut sim, ADDRESS),
+        AnalogFeedback(|| Ok::<_, Infallible>(position.get())),
+        gains(),
+        ServoConfig::new(2, 3.0).with_settle_time(50),
+    );
+    servo.set_target(100);
+    assert_eq!(servo.update(0).unwrap(), ServoStatus::Moving);
+    assert_eq!(servo.update(40_000).unwrap(), ServoStatus::Moving);
+    // Knocked out of the band: the settle time starts over once back in it
+    position.set(110);
+    assert_eq!(servo.update(45_000).unwrap(), ServoStatus::Moving);
+    position.set(101);
+    assert_eq!(servo.update(60_000).unwrap(), ServoStatus::Moving);
+    assert_eq!(servo.update(100_000).unwrap(), ServoStatus::Moving);
+    assert_eq!(servo.update(110_000).unwrap(), ServoStatus::Settled);
+    assert_eq!(servo.status(), ServoStatus::Settled);
+}
+
+#[test]
+fn times_out_and_coasts_until_retargeted() {
+    // Stalled: the position never changes
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    let mut servo = PositionController::new(
+        Drv8830::new(&mut sim, ADDRESS),
+        AnalogFeedback(|| Ok::<_, Infallible>(0)),
+        gains(),
+        ServoConfig::new(2, 3.0).with_timeout(100),
+    );
+    servo.set_target(100);
+    assert_eq!(servo.update(0).unwrap(), ServoStatus::Moving);
+    assert_eq!(servo.update(99_000).unwrap(), ServoStatus::Moving);
+    assert_eq!(servo.update(100_000).unwrap(), ServoStatus::TimedOut);
+    assert_eq!(servo.update(200_000).unwrap(), ServoStatus::TimedOut);
+    assert_eq!(servo.driver().read_control().unwrap(), Control::COAST);
+
+    // A new target restarts the timeout from its first update
+    servo.set_target(-100);
+    assert_eq!(servo.update(300_000).unwrap(), ServoStatus::Moving);
+    assert_eq!(
+        servo.driver().read_control().unwrap().direction(),
+        Direction::Reverse
+    );
+    assert_eq!(servo.update(399_000).unwrap(), ServoStatus::Moving);
+    assert_eq!(servo.update(400_000).unwrap(), ServoStatus::TimedOut);
+    assert_eq!(servo.driver().read_control().unwrap(), Control::COAST);
+}
+
+#[test]
+fn non_finite_voltage_limit_gives_no_output() {
+    for max_volts in [f32::NAN, f32::INFINITY] {
+        let mut sim = SimulatedDrv8830::new(ADDRESS);
+        let mut servo = PositionController::new(
+            Drv8830::new(&mut sim, ADDRESS),
+            AnalogFeedback(|| Ok::<_, Infallible>(0)),
+            gains(),
+            ServoConfig::new(2, max_volts),
+        );
+        servo.set_target(100);
+        assert_eq!(servo.update(0).unwrap(), ServoStatus::Moving);
+        assert_eq!(servo.update(1000).unwrap(), ServoStatus::Moving);
+        assert_eq!(servo.driver().read_control().unwrap(), Control::COAST);
+
+        // Also when set later
+        servo.set_config(ServoConfig::new(2, 3.0));
+        servo.update(2000).unwrap();
+        servo.set_config(ServoConfig::new(2, max_volts));
+        servo.update(3000).unwrap();
+        assert_eq!(servo.driver().read_control().unwrap(), Control::COAST);
+    }
+}
@@ -0,0 +1,276 @@
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use drv8830::sim::SimulatedDrv8830;
+use drv8830::{
+    Address, AddressPin, Control, Drv8830, DuplicateAddress, Error, FaultEvent, FaultKind,
+    FaultMonitor, MotorBank, Rounding, StopMode, VSet,
+};
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+use embedded_hal_bus::i2c::{AtomicDevice, CriticalSectionDevice, RefCellDevice};
+use embedded_hal_bus::util::AtomicCell;
+
+const LEFT: Address = Address::new(AddressPin::Low, AddressPin::Low);
+const RIGHT: Address = Address::new(AddressPin::Low, AddressPin::High);
+
+// IMU-like device answering a WHO_AM_I read
+const SENSOR_ADDRESS: u8 = 0x68;
+const WHO_AM_I: u8 = 0x75;
+const SENSOR_ID: u8 = 0x71;
+
+// Two motor drivers and a sensor on one bus
+struct Bus {
+    left: SimulatedDrv8830,
+    right: SimulatedDrv8830,
+    sensor_reads: u32,
+}
+
+impl Bus {
+    fn new() -> Self {
+        Self {
+            left: SimulatedDrv8830::new(LEFT),
+            right: SimulatedDrv8830::new(RIGHT),
+            sensor_reads: 0,
+        }
+    }
+
+    fn sensor(&mut self, operations: &mut [Operation<'_>]) -> Result<(), ErrorKind> {
+        let mut register = None;
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => register = bytes.first().copied(),
+                Operation::Read(buffer) if register == Some(WHO_AM_I) => {
+                    buffer.fill(SENSOR_ID);
+                    self.sensor_reads += 1;
+                }
+                Operation::Read(_) => {
+                    return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ErrorType for Bus {
+    type Error = ErrorKind;
+}
+
+impl I2c for Bus {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        match address {
+            SENSOR_ADDRESS => self.sensor(operations),
+            _ if address == LEFT.as_u8() => self.left.transaction(address, operations),
+            _ if address == RIGHT.as_u8() => self.right.transaction(address, operations),
+            _ => Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+        }
+    }
+}
+
+// Stand-in for another driver that owns its own bus device
+struct Sensor<I> {
+    i2c: I,
+}
+
+impl<I: I2c> Sensor<I> {
+    fn who_am_i(&mut self) -> Result<u8, I::Error> {
+        let mut id = [0];
+        self.i2c.write_read(SENSOR_ADDRESS, &[WHO_AM_I], &mut id)?;
+        Ok(id[0])
+    }
+}
+
+fn half_speed() -> VSet {
+    VSet::from_millivolts(2500, Rounding::Nearest).unwrap()
+}
+
+fn drive_all<I: I2c, S: I2c>(
+    left: &mut Drv8830<I>,
+    right: &mut Drv8830<I>,
+    sensor: &mut Sensor<S>,
+) {
+    left.forward(half_speed()).unwrap();
+    assert_eq!(sensor.who_am_i().ok(), Some(SENSOR_ID));
+    right.reverse(VSet::MAX).unwrap();
+    assert_eq!(sensor.who_am_i().ok(), Some(SENSOR_ID));
+    left.brake().unwrap();
+    assert_eq!(left.read_control().unwrap(), Control::BRAKE);
+    assert_eq!(right.read_control().unwrap(), Control::REVERSE);
+}
+
+fn assert_final_state(bus: &Bus) {
+    assert_eq!(bus.left.control(), Control::BRAKE.into());
+    assert_eq!(bus.right.control(), Control::REVERSE.into());
+    assert_eq!(bus.sensor_reads, 2);
+}
+
+#[test]
+fn ref_cell_devices_share_bus() {
+    let bus = RefCell::new(Bus::new());
+    let mut left = Drv8830::new(RefCellDevice::new(&bus), LEFT);
+    let mut right = Drv8830::new(RefCellDevice::new(&bus), RIGHT);
+    let mut sensor = Sensor {
+        i2c: RefCellDevice::new(&bus),
+    };
+    drive_all(&mut left, &mut right, &mut sensor);
+    assert_final_state(&bus.borrow());
+}
+
+#[test]
+fn critical_section_devices_share_bus() {
+    let bus = Mutex::new(RefCell::new(Bus::new()));
+    let mut left = Drv8830::new(CriticalSectionDevice::new(&bus), LEFT);
+    let mut right = Drv8830::new(CriticalSectionDevice::new(&bus), RIGHT);
+    let mut sensor = Sensor {
+        i2c: CriticalSectionDevice::new(&bus),
+    };
+    drive_all(&mut left, &mut right, &mut sensor);
+    critical_section::with(|cs| assert_final_state(&bus.borrow_ref(cs)));
+}
+
+#[test]
+fn atomic_devices_share_bus() {
+    let bus = AtomicCell::new(Bus::new());
+    let mut left = Drv8830::new(AtomicDevice::new(&bus), LEFT);
+    let mut right = Drv8830::new(AtomicDevice::new(&bus), RIGHT);
+    let mut sensor = Sensor {
+        i2c: AtomicDevice::new(&bus),
+    };
+    // The cell gives no access to the bus afterwards, so `drive_all` reading the
+    // registers back through the devices is the check here
+    drive_all(&mut left, &mut right, &mut sensor);
+}
+
+#[test]
+fn motor_bank_shares_bus() {
+    let bus = RefCell::new(Bus::new());
+    let mut bank = MotorBank::new(RefCellDevice::new(&bus), [LEFT, RIGHT]).unwrap();
+    let mut sensor = Sensor {
+        i2c: RefCellDevice::new(&bus),
+    };
+    assert!(bank.brake_all().iter().all(Result::is_ok));
+    assert_eq!(sensor.who_am_i().ok(), Some(SENSOR_ID));
+    assert!(bank
+        .read_faults()
+        .iter()
+        .all(|fault| fault.as_ref().is_ok_and(|f| !f.fault)));
+    assert_eq!(bus.borrow().right.control(), Control::BRAKE.into());
+}
+
+#[test]
+fn motor_bank_rejects_duplicate_addresses() {
+    let bus = RefCell::new(Bus::new());
+    assert_eq!(
+        MotorBank::new(RefCellDevice::new(&bus), [LEFT, RIGHT, LEFT]).err(),
+        Some(DuplicateAddress(LEFT))
+    );
+}
+
+#[test]
+fn motor_bank_applies_per_motor_config() {
+    let bus = RefCell::new(Bus::new());
+    let mut bank = MotorBank::new(RefCellDevice::new(&bus), [LEFT, RIGHT]).unwrap();
+    bank.set_deadband(0, 1000);
+    bank.set_stop_mode(0, StopMode::Brake);
+    bank.set_verify(1, Some(1));
+    bank.set_cache(1, true);
+
+    let results = bank.each(|driver| driver.set_voltage_signed(900));
+    assert!(results.iter().all(Result::is_ok));
+    // Only the left motor has the wider deadband
+    assert_eq!(bus.borrow().left.control(), Control::BRAKE.into());
+    assert_eq!(
+        bus.borrow().right.control(),
+        Control::from_signed_millivolts(900, StopMode::Coast)
+            .unwrap()
+            .into()
+    );
+
+    // Verified and cached: the repeated write is skipped, the lost one retried
+    bus.borrow_mut().right.inject_lost_writes(1);
+    let mut right = bank.motor(1).unwrap();
+    right.brake().unwrap();
+    right.brake().unwrap();
+    assert_eq!(bus.borrow().right.control(), Control::BRAKE.into());
+    // The signed write and the retried brake; lost writes are not counted
+    assert_eq!(bus.borrow().right.register_writes(), 2);
+}
+
+#[test]
+fn motor_bank_cache_survives_grouped_commands() {
+    let bus = RefCell::new(Bus::new());
+    let mut bank = MotorBank::new(RefCellDevice::new(&bus), [LEFT, RIGHT]).unwrap();
+    bank.set_cache(0, true);
+    for _ in 0..3 {
+        assert!(bank.brake_all().iter().all(Result::is_ok));
+    }
+    // Also kept across handles from `motor`
+    bank.motor(0).unwrap().brake().unwrap();
+    assert_eq!(bus.borrow().left.register_writes(), 1);
+    assert_eq!(bus.borrow().right.register_writes(), 3);
+
+    bank.motor(0).unwrap().coast().unwrap();
+    assert!(bank.coast_all().iter().all(Result::is_ok));
+    assert_eq!(bus.borrow().left.register_writes(), 2);
+}
+
+#[test]
+fn motor_bank_failing_chip_does_not_stop_the_others() {
+    let bus = RefCell::new(Bus::new());
+    let mut bank = MotorBank::new(RefCellDevice::new(&bus), [LEFT, RIGHT]).unwrap();
+    bus.borrow_mut().left.inject_nacks(1);
+    let [left, right] = bank.brake_all();
+    assert_eq!(
+        left,
+        Err(Error::Bus(ErrorKind::NoAcknowledge(
+            NoAcknowledgeSource::Address
+        )))
+    );
+    assert_eq!(right, Ok(()));
+    assert_eq!(bus.borrow().right.control(), Control::BRAKE.into());
+
+    bus.borrow_mut().right.inject_nacks(1);
+    let [left, right] = bank.read_faults();
+    assert!(left.is_ok_and(|fault| !fault.fault));
+    assert!(right.is_err());
+}
+
+#[test]
+fn motor_bank_names() {
+    let bus = RefCell::new(Bus::new());
+    let mut bank =
+        MotorBank::with_names(RefCellDevice::new(&bus), [("left", LEFT), ("right", RIGHT)])
+            .unwrap();
+    assert_eq!(bank.index_of("right"), Some(1));
+    assert_eq!(bank.address(1), Some(RIGHT));
+    assert!(bank.by_name("middle").is_none());
+    bank.by_name("right").unwrap().forward(VSet::MAX).unwrap();
+    assert_eq!(bus.borrow().right.control(), Control::FORWARD.into());
+    assert_eq!(bus.borrow().left.register_writes(), 0);
+}
+
+#[test]
+fn motor_bank_polls_one_monitor_per_chip() {
+    let bus = RefCell::new(Bus::new());
+    let mut bank = MotorBank::new(RefCellDevice::new(&bus), [LEFT, RIGHT]).unwrap();
+    let mut monitors = [FaultMonitor::default(), FaultMonitor::default()];
+    bus.borrow_mut().right.inject_fault(FaultKind::Ocp);
+    let [left, right] = bank.poll_faults(&mut monitors, 0);
+    assert!(left.unwrap().is_empty());
+    assert_eq!(
+        right.unwrap().collect::<Vec<_>>(),
+        [
+            FaultEvent::Raised(FaultKind::Ocp),
+            FaultEvent::Retried {
+                kind: FaultKind::Ocp,
+                attempt: 1
+            }
+        ]
+    );
+    assert!(monitors[1].is_active(FaultKind::Ocp) && !monitors[0].is_active(FaultKind::Ocp));
+    assert!(!bus.borrow().right.fault().fault());
+}
@@ -0,0 +1,165 @@
+use core::cell::RefCell;
+
+use drv8830::sim::SimulatedDrv8830;
+use drv8830::{
+    Address, AddressPin, Control, Direction, Drv8830, Error, FaultEvent, FaultKind, FaultMonitor,
+    VSet,
+};
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+use embedded_hal_bus::i2c::RefCellDevice;
+
+const ADDRESS: Address = Address::new(AddressPin::Open, AddressPin::High);
+
+#[test]
+fn commands_reach_control_register() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    let mut driver = Drv8830::new(&mut sim, ADDRESS);
+    driver.reverse(VSet::from_code(0x20).unwrap()).unwrap();
+    assert_eq!(
+        driver.read_control().unwrap(),
+        Control::from_direction(Direction::Reverse, VSet::from_code(0x20).unwrap())
+    );
+    assert_eq!(sim.control().vset_code(), 0x20);
+    assert!(sim.control().in2() && !sim.control().in1());
+}
+
+#[test]
+fn other_addresses_are_not_acknowledged() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    let mut driver = Drv8830::new(&mut sim, Address::default());
+    assert_eq!(
+        driver.brake(),
+        Err(Error::Bus(ErrorKind::NoAcknowledge(
+            NoAcknowledgeSource::Address
+        )))
+    );
+}
+
+#[test]
+fn injected_nacks_fail_the_next_transactions() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    sim.inject_nacks(2);
+    let mut driver = Drv8830::new(&mut sim, ADDRESS);
+    let nack = Err(Error::Bus(ErrorKind::NoAcknowledge(
+        NoAcknowledgeSource::Address,
+    )));
+    assert_eq!(driver.brake(), nack);
+    assert_eq!(driver.read_fault().map(|_| ()), nack);
+    assert_eq!(driver.brake(), Ok(()));
+    assert_eq!(driver.read_control().unwrap(), Control::BRAKE);
+    assert_eq!(sim.transactions(), 4);
+    assert_eq!(sim.register_writes(), 1);
+}
+
+#[test]
+fn power_up_control_has_reserved_vset() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    let mut driver = Drv8830::new(&mut sim, ADDRESS);
+    let control = driver.read_control().unwrap();
+    assert_eq!(control.direction(), Direction::Coast);
+    assert_eq!(control.vset(), None);
+    assert_eq!(control.control(), None);
+}
+
+#[test]
+fn verify_retries_lost_writes() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    sim.inject_lost_writes(2);
+    let mut driver = Drv8830::new(&mut sim, ADDRESS).with_verify(2);
+    driver.brake().unwrap();
+    assert_eq!(sim.control(), Control::BRAKE.into());
+
+    sim.inject_lost_writes(3);
+    let mut driver = Drv8830::new(&mut sim, ADDRESS).with_verify(2);
+    assert!(matches!(driver.coast(), Err(Error::VerifyMismatch { .. })));
+}
+
+#[test]
+fn cache_skips_redundant_writes() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    let mut driver = Drv8830::new(&mut sim, ADDRESS).with_cache();
+    driver.forward(VSet::MAX).unwrap();
+    driver.forward(VSet::MAX).unwrap();
+    driver.force_write(Control::FORWARD).unwrap();
+    driver.invalidate_cache();
+    driver.forward(VSet::MAX).unwrap();
+    assert_eq!(driver.cached_control(), Some(Control::FORWARD));
+    assert_eq!(sim.register_writes(), 3);
+}
+
+#[test]
+fn cache_never_skips_fault_clear() {
+    let sim = RefCell::new(SimulatedDrv8830::new(ADDRESS));
+    let mut driver = Drv8830::new(RefCellDevice::new(&sim), ADDRESS).with_cache();
+    assert!(!driver.read_fault().unwrap().fault);
+    // Latches after the read, so the cached copy still says no fault
+    sim.borrow_mut().inject_fault(FaultKind::Ocp);
+    driver.clear_faults().unwrap();
+    assert_eq!(sim.borrow().fault().bits(), 0);
+}
+
+#[test]
+fn read_and_clear_keeps_snapshot() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    sim.inject_fault(FaultKind::Ocp);
+    sim.set_condition(FaultKind::Ots, true);
+    let mut driver = Drv8830::new(&mut sim, ADDRESS);
+    let fault = driver.read_and_clear().unwrap();
+    assert!(fault.fault && fault.ocp && fault.ots);
+
+    // Overtemperature is still present, so it latches again
+    let fault = driver.read_fault().unwrap();
+    assert!(fault.fault && fault.ots && !fault.ocp);
+
+    sim.set_condition(FaultKind::Ots, false);
+    let mut driver = Drv8830::new(&mut sim, ADDRESS);
+    driver.clear_faults().unwrap();
+    assert!(driver.check_fault().is_ok());
+}
+
+#[test]
+fn monitor_latches_persistent_overcurrent() {
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    sim.set_condition(FaultKind::Ocp, true);
+    let mut driver = Drv8830::new(&mut sim, ADDRESS);
+    driver.forward(VSet::MAX).unwrap();
+
+    let mut monitor = FaultMonitor::default();
+    let mut events = Vec::new();
+    for now_ms in 0..5 {
+        events.extend(monitor.poll(&mut driver, now_ms * 1000).unwrap());
+    }
+    assert!(monitor.is_latched());
+    assert_eq!(events.first(), Some(&FaultEvent::Raised(FaultKind::Ocp)));
+    assert_eq!(events.last(), Some(&FaultEvent::Latched(FaultKind::Ocp)));
+    assert_eq!(driver.read_control().unwrap(), Control::COAST);
+}
+
+#[cfg(feature = "float")]
+#[test]
+fn stalled_motor_trips_current_limit() {
+    use drv8830::sim::{DcMotor, MotorParams};
+    use drv8830::Rounding;
+
+    let mut sim = SimulatedDrv8830::new(ADDRESS);
+    sim.attach_motor(DcMotor::new(MotorParams::default()));
+    sim.set_current_limit(Some(0.3));
+    let mut driver = Drv8830::new(&mut sim, ADDRESS);
+    driver
+        .forward(VSet::from_millivolts(3000, Rounding::Nearest).unwrap())
+        .unwrap();
+
+    // Free running settles well below the limit
+    sim.step(1.0);
+    let motor = sim.motor().unwrap();
+    assert!(motor.speed() > 1000.0 && motor.current() < 0.1);
+    assert!(sim.bridge_enabled());
+
+    // Stalled, the chip holds the limit for 275 ms and then shuts down
+    sim.motor_mut().unwrap().set_load_torque(0.01);
+    sim.step(0.2);
+    assert!(sim.bridge_enabled());
+    sim.step(0.1);
+    assert!(sim.fault().i_limit() && !sim.bridge_enabled());
+    assert_eq!(sim.motor().unwrap().current(), 0.0);
+}
@@ -0,0 +1,137 @@
+use core::cell::{Cell, RefCell};
+use core::f32::consts::TAU;
+
+use drv8830::sim::{DcMotor, MotorParams, SimulatedDrv8830};
+use drv8830::{
+    Address, AddressPin, Control, ControlError, Drv8830, Encoder, EncoderReading, FeedForward,
+    PidGains, SpeedController, StopMode,
+};
+use embedded_hal_bus::i2c::RefCellDevice;
+
+const ADDRESS: Address = Address::new(AddressPin::Open, AddressPin::Low);
+const COUNTS_PER_REV: u32 = 1000;
+const PERIOD_US: u64 = 5000;
+
+// Reads the simulated shaft angle as encoder counts
+struct SimEncoder<'a> {
+    sim: &'a RefCell<SimulatedDrv8830>,
+    now_us: &'a Cell<u64>,
+    fail: &'a Cell<bool>,
+}
+
+#[derive(Debug, PartialEq)]
+struct EncoderFailed;
+
+impl Encoder for SimEncoder<'_> {
+    type Error = EncoderFailed;
+
+    fn read(&mut self) -> Result<EncoderReading, Self::Error> {
+        if self.fail.get() {
+            return Err(EncoderFailed);
+        }
+        let radians = self.sim.borrow().motor().unwrap().position();
+        Ok(EncoderReading {
+            count: (radians * COUNTS_PER_REV as f32 / TAU) as i32,
+            timestamp_us: self.now_us.get(),
+        })
+    }
+}
+
+struct Rig {
+    sim: RefCell<SimulatedDrv8830>,
+    now_us: Cell<u64>,
+    fail: Cell<bool>,
+}
+
+impl Rig {
+    fn new() -> Self {
+        let mut sim = SimulatedDrv8830::new(ADDRESS);
+        sim.attach_motor(DcMotor::new(MotorParams::default()));
+        Self {
+            sim: RefCell::new(sim),
+            now_us: Cell::new(0),
+            fail: Cell::new(false),
+        }
+    }
+
+    fn controller(&self) -> SpeedController<RefCellDevice<'_, SimulatedDrv8830>, SimEncoder<'_>> {
+        let driver =
+            Drv8830::new(RefCellDevice::new(&self.sim), ADDRESS).with_stop_mode(StopMode::Brake);
+        let encoder = SimEncoder {
+            sim: &self.sim,
+            now_us: &self.now_us,
+            fail: &self.fail,
+        };
+        let ke = MotorParams::default().ke;
+        SpeedController::new(driver, encoder, PidGains::new(2e-5, 5e-4, 0.0))
+            .with_feed_forward(FeedForward::from_back_emf(ke, COUNTS_PER_REV))
+    }
+
+    // Run for `ms`, returning the mean shaft speed over the second half in counts/s
+    fn run(
+        &self,
+        controller: &mut SpeedController<RefCellDevice<'_, SimulatedDrv8830>, SimEncoder<'_>>,
+        ms: u64,
+    ) -> f32 {
+        let steps = ms * 1000 / PERIOD_US;
+        let mut sum = 0.0;
+        for i in 0..steps {
+            controller.update().unwrap();
+            self.sim.borrow_mut().step(PERIOD_US as f32 * 1e-6);
+            self.now_us.set(self.now_us.get() + PERIOD_US);
+            if i >= steps / 2 {
+                sum += self.sim.borrow().motor().unwrap().speed();
+            }
+        }
+        sum / (steps - steps / 2) as f32 * COUNTS_PER_REV as f32 / TAU
+    }
+}
+
+#[test]
+fn reaches_and_holds_target_speed() {
+    let rig = Rig::new();
+    let mut controller = rig.controller();
+    controller.set_target(100_000.0);
+    let speed = rig.run(&mut controller, 1000);
+    assert!((speed / 100_000.0 - 1.0).abs() < 0.03, "{speed}");
+    let unloaded_volts = controller.output_volts();
+
+    // Under load the integral makes up for the extra torque
+    rig.sim
+        .borrow_mut()
+        .motor_mut()
+        .unwrap()
+        .set_load_torque(0.5e-3);
+    let speed = rig.run(&mut controller, 2000);
+    assert!((speed / 100_000.0 - 1.0).abs() < 0.03, "{speed}");
+    assert!(controller.output_volts() > unloaded_volts + 0.5);
+
+    controller.set_target(-50_000.0);
+    let speed = rig.run(&mut controller, 2000);
+    assert!((speed / -50_000.0 - 1.0).abs() < 0.03, "{speed}");
+}
+
+#[test]
+fn zero_target_uses_stop_mode() {
+    let rig = Rig::new();
+    let mut controller = rig.controller();
+    controller.set_target(100_000.0);
+    rig.run(&mut controller, 200);
+    controller.set_target(0.0);
+    rig.run(&mut controller, 10);
+    assert_eq!(rig.sim.borrow().control(), Control::BRAKE.into());
+    assert_eq!(controller.output_volts(), 0.0);
+}
+
+#[test]
+fn encoder_errors_are_feedback_errors() {
+    let rig = Rig::new();
+    let mut controller = rig.controller();
+    controller.set_target(100_000.0);
+    rig.run(&mut controller, 20);
+    rig.fail.set(true);
+    assert!(matches!(
+        controller.update(),
+        Err(ControlError::Feedback(EncoderFailed))
+    ));
+}
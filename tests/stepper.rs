@@ -0,0 +1,264 @@
+use core::cell::RefCell;
+
+use drv8830::sim::SimulatedDrv8830;
+use drv8830::{
+    Address, AddressPin, Control, ControlReadback, Direction, Drv8830, StepDirection, StepMode,
+    Stepper, StepperConfig, VSet,
+};
+use embedded_hal_bus::i2c::RefCellDevice;
+
+const COIL_A: Address = Address::new(AddressPin::Low, AddressPin::Low);
+const COIL_B: Address = Address::new(AddressPin::Low, AddressPin::High);
+
+type Sim = RefCell<SimulatedDrv8830>;
+
+fn sims() -> (Sim, Sim) {
+    (
+        RefCell::new(SimulatedDrv8830::new(COIL_A)),
+        RefCell::new(SimulatedDrv8830::new(COIL_B)),
+    )
+}
+
+fn new_stepper<'a>(
+    a: &'a Sim,
+    b: &'a Sim,
+    config: StepperConfig,
+) -> Stepper<RefCellDevice<'a, SimulatedDrv8830>> {
+    Stepper::new(
+        Drv8830::new(RefCellDevice::new(a), COIL_A),
+        Drv8830::new(RefCellDevice::new(b), COIL_B),
+        config,
+    )
+}
+
+fn readback(sim: &Sim) -> ControlReadback {
+    ControlReadback::from(sim.borrow().control())
+}
+
+// Coil polarity from IN1/IN2: 1 forward, -1 reverse, 0 coasting
+fn polarity(sim: &Sim) -> i8 {
+    match readback(sim).direction() {
+        Direction::Forward => 1,
+        Direction::Reverse => -1,
+        Direction::Coast => 0,
+        Direction::Brake => panic!("coil braked"),
+    }
+}
+
+fn coils(a: &Sim, b: &Sim) -> (i8, i8) {
+    (polarity(a), polarity(b))
+}
+
+// Coil pattern after each of `steps` single steps
+fn pattern(mode: StepMode, direction: StepDirection, steps: usize) -> Vec<(i8, i8)> {
+    let (a, b) = sims();
+    let mut stepper = new_stepper(&a, &b, StepperConfig::new(mode, 100, VSet::MAX));
+    (0..steps)
+        .map(|_| {
+            stepper.step(direction).unwrap();
+            coils(&a, &b)
+        })
+        .collect()
+}
+
+// Timestamps of every step of a move, ticking every 10 us
+fn step_times(stepper: &mut Stepper<RefCellDevice<'_, SimulatedDrv8830>>) -> Vec<(u64, i32)> {
+    let mut times = Vec::new();
+    let mut position = stepper.position();
+    let mut now_us = 0;
+    while !stepper.tick(now_us).unwrap() {
+        if stepper.position() != position {
+            position = stepper.position();
+            times.push((now_us, position));
+        }
+        now_us += 10;
+        assert!(now_us < 10_000_000, "move did not finish");
+    }
+    if stepper.position() != position {
+        times.push((now_us, stepper.position()));
+    }
+    times
+}
+
+fn intervals(times: &[(u64, i32)]) -> Vec<u64> {
+    times.windows(2).map(|pair| pair[1].0 - pair[0].0).collect()
+}
+
+#[test]
+fn wave_drive_energises_one_coil_at_a_time() {
+    assert_eq!(
+        pattern(StepMode::Wave, StepDirection::Forward, 4),
+        [(0, 1), (-1, 0), (0, -1), (1, 0)]
+    );
+    assert_eq!(
+        pattern(StepMode::Wave, StepDirection::Reverse, 4),
+        [(0, -1), (-1, 0), (0, 1), (1, 0)]
+    );
+}
+
+#[test]
+fn full_step_energises_both_coils() {
+    assert_eq!(
+        pattern(StepMode::FullStep, StepDirection::Forward, 4),
+        [(-1, 1), (-1, -1), (1, -1), (1, 1)]
+    );
+    assert_eq!(
+        pattern(StepMode::FullStep, StepDirection::Reverse, 4),
+        [(1, -1), (-1, -1), (-1, 1), (1, 1)]
+    );
+}
+
+#[test]
+fn half_step_alternates_one_and_two_coils() {
+    assert_eq!(
+        pattern(StepMode::HalfStep, StepDirection::Forward, 8),
+        [
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (1, 0)
+        ]
+    );
+}
+
+#[test]
+fn steps_are_evenly_spaced_without_acceleration() {
+    let (a, b) = sims();
+    let mut stepper = new_stepper(
+        &a,
+        &b,
+        StepperConfig::new(StepMode::FullStep, 100, VSet::MAX),
+    );
+    stepper.move_to(5);
+    let times = step_times(&mut stepper);
+    assert_eq!(
+        times.iter().map(|&(_, p)| p).collect::<Vec<_>>(),
+        [1, 2, 3, 4, 5]
+    );
+    assert_eq!(intervals(&times), [10_000; 4]);
+}
+
+#[test]
+fn acceleration_ramps_step_rate_up_and_down() {
+    let (a, b) = sims();
+    let config = StepperConfig::new(StepMode::FullStep, 1000, VSet::MAX).with_acceleration(1000);
+    let mut stepper = new_stepper(&a, &b, config);
+    stepper.move_to(40);
+    let times = step_times(&mut stepper);
+    assert_eq!(times.len(), 40);
+    let intervals = intervals(&times);
+    let fastest = intervals
+        .iter()
+        .position(|i| i == intervals.iter().min().unwrap())
+        .unwrap();
+    // Shorter and shorter steps, then longer again to stop on the target
+    assert!(intervals[..fastest]
+        .windows(2)
+        .all(|pair| pair[1] <= pair[0]));
+    assert!(intervals[fastest..]
+        .windows(2)
+        .all(|pair| pair[1] >= pair[0]));
+    assert!(fastest > 5 && fastest < intervals.len() - 5);
+    // First step after standstill at sqrt(2a) = 44.7 steps/s
+    assert!(intervals[0].abs_diff(22_360) <= 20, "{}", intervals[0]);
+    assert!(intervals[0].abs_diff(*intervals.last().unwrap()) <= 2_000);
+    // Never above the 1000 steps/s limit
+    assert!(intervals.iter().all(|&interval| interval >= 1000));
+}
+
+#[test]
+fn reversal_decelerates_first() {
+    let (a, b) = sims();
+    let config = StepperConfig::new(StepMode::FullStep, 1000, VSet::MAX).with_acceleration(1000);
+    let mut stepper = new_stepper(&a, &b, config);
+    stepper.move_to(1000);
+    let mut now_us = 0;
+    while stepper.position() < 30 {
+        stepper.tick(now_us).unwrap();
+        now_us += 10;
+    }
+    let speed = stepper.speed_steps_per_s();
+    stepper.move_to(0);
+    let mut positions = vec![stepper.position()];
+    while !stepper.tick(now_us).unwrap() {
+        if stepper.position() != *positions.last().unwrap() {
+            positions.push(stepper.position());
+        }
+        now_us += 10;
+    }
+    positions.push(stepper.position());
+    let peak = *positions.iter().max().unwrap();
+    // Carries on forward while braking: v^2 / 2a steps
+    let braking = (speed * speed / 2000) as i32;
+    assert!(
+        peak > 30 && (peak - 30 - braking).abs() <= 2,
+        "{peak} {braking}"
+    );
+    let turn = positions.iter().position(|&p| p == peak).unwrap();
+    assert!(positions[..=turn]
+        .windows(2)
+        .all(|pair| pair[1] == pair[0] + 1));
+    assert!(positions[turn..].windows(2).all(|pair| pair[1] <= pair[0]));
+    assert_eq!(stepper.position(), 0);
+}
+
+#[test]
+fn holds_or_coasts_at_the_end_of_a_move() {
+    let hold = VSet::MIN;
+    let (a, b) = sims();
+    let config = StepperConfig::new(StepMode::FullStep, 100, VSet::MAX).with_hold(Some(hold));
+    let mut stepper = new_stepper(&a, &b, config);
+    stepper.move_to(3);
+    step_times(&mut stepper);
+    assert_eq!(coils(&a, &b), (1, -1));
+    assert_eq!(readback(&a).vset(), Some(hold));
+    assert_eq!(readback(&b).vset(), Some(hold));
+
+    let (a, b) = sims();
+    let config = StepperConfig::new(StepMode::FullStep, 100, VSet::MAX).with_hold(None);
+    let mut stepper = new_stepper(&a, &b, config);
+    stepper.move_to(3);
+    step_times(&mut stepper);
+    assert_eq!(coils(&a, &b), (0, 0));
+}
+
+#[test]
+fn release_is_not_skipped_after_direct_coil_access() {
+    let (a, b) = sims();
+    let mut stepper = new_stepper(
+        &a,
+        &b,
+        StepperConfig::new(StepMode::FullStep, 100, VSet::MAX),
+    );
+    stepper.coils()[0].brake().unwrap();
+    stepper.coils()[1].forward(VSet::MAX).unwrap();
+    stepper.release().unwrap();
+    assert_eq!(a.borrow().control(), Control::COAST.into());
+    assert_eq!(b.borrow().control(), Control::COAST.into());
+}
+
+#[test]
+fn far_targets_do_not_overflow() {
+    let (a, b) = sims();
+    let config = StepperConfig::new(StepMode::FullStep, 1000, VSet::MAX);
+    let mut stepper = new_stepper(&a, &b, config);
+    stepper.set_position(5);
+    stepper.move_to(i32::MIN);
+    let mut now_us = 0;
+    for _ in 0..3 {
+        stepper.tick(now_us).unwrap();
+        now_us += 1000;
+    }
+    assert_eq!(stepper.position(), 2);
+
+    // Shifting the frame keeps the distance to go, saturating at the range
+    stepper.move_to(i32::MAX);
+    stepper.set_position(10);
+    assert_eq!(stepper.target(), i32::MAX);
+    stepper.set_position(i32::MIN);
+    assert_eq!(stepper.target(), i32::MIN + (i32::MAX - 10));
+}
@@ -0,0 +1,80 @@
+use drv8830::{InvalidVoltage, Rounding, VSet};
+
+const ROUNDINGS: [Rounding; 3] = [Rounding::Floor, Rounding::Ceil, Rounding::Nearest];
+
+fn code(code: u8) -> Result<VSet, InvalidVoltage> {
+    VSet::from_code(code)
+}
+
+#[test]
+fn table_entries_match_exactly() {
+    for raw in 0x06..=0x3F {
+        let vset = code(raw).unwrap();
+        for rounding in ROUNDINGS {
+            assert_eq!(
+                VSet::from_millivolts(vset.to_millivolts(), rounding),
+                Ok(vset)
+            );
+        }
+    }
+    assert_eq!(code(0x10).unwrap().to_millivolts(), 1290);
+    assert_eq!(code(0x30).unwrap().to_millivolts(), 3860);
+}
+
+#[test]
+fn rounding_between_entries() {
+    // 960 mV (0x0C) and 1040 mV (0x0D)
+    assert_eq!(VSet::from_millivolts(1010, Rounding::Floor), code(0x0C));
+    assert_eq!(VSet::from_millivolts(1010, Rounding::Ceil), code(0x0D));
+    assert_eq!(VSet::from_millivolts(1010, Rounding::Nearest), code(0x0D));
+    assert_eq!(VSet::from_millivolts(961, Rounding::Ceil), code(0x0D));
+    assert_eq!(VSet::from_millivolts(1039, Rounding::Floor), code(0x0C));
+    assert_eq!(VSet::from_millivolts(999, Rounding::Nearest), code(0x0C));
+    // 1200 mV (0x0F) and 1290 mV (0x10): halfway goes to the lower voltage
+    assert_eq!(VSet::from_millivolts(1245, Rounding::Nearest), code(0x0F));
+    assert_eq!(VSet::from_millivolts(1246, Rounding::Nearest), code(0x10));
+}
+
+#[test]
+fn endpoints() {
+    for rounding in ROUNDINGS {
+        assert_eq!(VSet::from_millivolts(480, rounding), Ok(VSet::MIN));
+        assert_eq!(VSet::from_millivolts(5060, rounding), Ok(VSet::MAX));
+    }
+    assert_eq!(VSet::MIN.to_millivolts(), VSet::MIN_MILLIVOLTS);
+    assert_eq!(VSet::MAX.to_millivolts(), VSet::MAX_MILLIVOLTS);
+    assert_eq!(VSet::MIN.code(), 0x06);
+    assert_eq!(VSet::MAX.code(), 0x3F);
+}
+
+#[test]
+fn out_of_range_is_rejected() {
+    for rounding in ROUNDINGS {
+        for millivolts in [0, 479, 5061, u16::MAX] {
+            assert_eq!(
+                VSet::from_millivolts(millivolts, rounding),
+                Err(InvalidVoltage)
+            );
+        }
+    }
+    for raw in [0x00, 0x05, 0x40, 0xFF] {
+        assert_eq!(code(raw), Err(InvalidVoltage));
+    }
+}
+
+#[cfg(feature = "float")]
+#[test]
+fn from_volts() {
+    for rounding in ROUNDINGS {
+        assert_eq!(VSet::from_volts(0.48, rounding), Ok(VSet::MIN));
+        assert_eq!(VSet::from_volts(5.06, rounding), Ok(VSet::MAX));
+        for volts in [f32::NAN, -0.1, 0.0, 0.479, 5.061, 70.0, f32::INFINITY] {
+            assert_eq!(VSet::from_volts(volts, rounding), Err(InvalidVoltage));
+        }
+    }
+    assert_eq!(VSet::from_volts(1.01, Rounding::Floor), code(0x0C));
+    assert_eq!(VSet::from_volts(1.01, Rounding::Ceil), code(0x0D));
+    assert_eq!(VSet::from_volts(1.01, Rounding::Nearest), code(0x0D));
+    assert_eq!(VSet::from_volts(1.0396, Rounding::Nearest), code(0x0D));
+    assert_eq!(code(0x0D).unwrap().to_volts(), 1.04);
+}